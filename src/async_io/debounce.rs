@@ -0,0 +1,14 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+#[cfg(test)]
+mod tests {
+    use async_io::Timer;
+
+    use crate::testing::debounce_bursts;
+
+    #[test]
+    fn debounce() {
+        assert_eq!(vec![2, 4, 5], debounce_bursts::<Timer>());
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    ops::ControlFlow,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+
+/// Limits how many _Ready_ items are polled in a row from an input stream.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReadyBudget {
+    /// Poll at most the given number of items.
+    MaxCount(NonZeroUsize),
+
+    /// Poll items while the cooperative scheduling budget of the
+    /// current tokio task allows.
+    #[cfg(feature = "tokio")]
+    TokioCoop,
+}
+
+/// Outcome of [`ReadyBudget::poll_ready_items()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadyItems {
+    /// The input stream is not ready.
+    Pending,
+
+    /// The input stream has finished and must not be polled again.
+    Finished,
+
+    /// The budget has been exhausted while the input stream may still be ready.
+    ///
+    /// The task has been scheduled to be woken up again.
+    Exhausted,
+
+    /// Polling has been stopped by the caller after receiving an item.
+    ///
+    /// The task has not been scheduled to be woken up again.
+    Interrupted,
+}
+
+impl ReadyBudget {
+    /// Polls the input stream while it yields items and the budget allows.
+    pub(crate) fn poll_ready_items<St: Stream>(
+        self,
+        stream: Pin<&mut St>,
+        cx: &mut Context<'_>,
+        mut on_item: impl FnMut(&mut Context<'_>, St::Item),
+    ) -> ReadyItems {
+        self.try_poll_ready_items(stream, cx, |cx, item| {
+            on_item(cx, item);
+            ControlFlow::Continue(())
+        })
+    }
+
+    /// Polls the input stream while it yields items, the budget allows,
+    /// and `on_item` continues.
+    pub(crate) fn try_poll_ready_items<St: Stream>(
+        self,
+        mut stream: Pin<&mut St>,
+        cx: &mut Context<'_>,
+        mut on_item: impl FnMut(&mut Context<'_>, St::Item) -> ControlFlow<()>,
+    ) -> ReadyItems {
+        let mut ready_count = 0;
+        loop {
+            #[cfg(feature = "tokio")]
+            let coop = if matches!(self, Self::TokioCoop) {
+                let Poll::Ready(coop) = tokio::task::coop::poll_proceed(cx) else {
+                    // The task will be woken up by the runtime.
+                    return ReadyItems::Exhausted;
+                };
+                if !tokio::task::coop::has_budget_remaining() {
+                    // Give back the last unit of the budget for polling the timer.
+                    // Otherwise the timer would never be polled successfully for
+                    // input streams that are always ready.
+                    drop(coop);
+                    cx.waker().wake_by_ref();
+                    return ReadyItems::Exhausted;
+                }
+                Some(coop)
+            } else {
+                None
+            };
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    #[cfg(feature = "tokio")]
+                    if let Some(coop) = coop {
+                        coop.made_progress();
+                    }
+                    if on_item(cx, item).is_break() {
+                        return ReadyItems::Interrupted;
+                    }
+                    ready_count += 1;
+                    if let Self::MaxCount(max_count) = self
+                        && ready_count >= max_count.get()
+                    {
+                        // Stop polling the input stream to prevent endless loops
+                        // for streams that are always ready. Wake ourselves up
+                        // to ensure that polling the stream continues afterwards.
+                        cx.waker().wake_by_ref();
+                        return ReadyItems::Exhausted;
+                    }
+                }
+                Poll::Ready(None) => return ReadyItems::Finished,
+                Poll::Pending => return ReadyItems::Pending,
+            }
+        }
+    }
+}
@@ -1,72 +1,421 @@
 // SPDX-FileCopyrightText: The futures-stream-ext authors
 // SPDX-License-Identifier: MPL-2.0
 
-use std::{
+use core::{
+    num::NonZeroUsize,
+    ops::ControlFlow,
     pin::Pin,
-    task::{Context, Poll, ready},
+    task::{Context, Poll},
     time::Duration,
 };
 
 use futures_util::Stream;
 use pin_project_lite::pin_project;
 
-use crate::Sleep;
+use crate::{
+    Sleep,
+    budget::{ReadyBudget, ReadyItems},
+    restart_sleep,
+};
+
+#[cfg(feature = "alloc")]
+mod adaptive;
+#[cfg(feature = "alloc")]
+pub use self::adaptive::{AdaptiveDebounceConfig, AdaptiveDebounced};
+
+mod distinct;
+pub use self::distinct::DebouncedDistinct;
+
+mod flush;
+pub use self::flush::{DebouncedFlushIf, DebouncedOk, FlushPolicy};
+
+mod fold;
+pub use self::fold::DebouncedFold;
+
+#[cfg(feature = "alloc")]
+mod keyed;
+#[cfg(feature = "alloc")]
+pub use self::keyed::{DebounceByKeyConfig, DebouncedByKey};
+
+#[cfg(feature = "alloc")]
+mod session;
+#[cfg(feature = "alloc")]
+pub use self::session::{SessionWindowConfig, SessionWindows};
+
+mod per_item;
+pub use self::per_item::{DebouncedBy, DebouncedWith};
+
+#[cfg(feature = "std")]
+mod group;
+#[cfg(feature = "std")]
+pub use self::group::{DebounceGroup, DebounceGroupDriver, GroupDebounced};
+
+/// Debounce edge trigger variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DebounceEdge {
+    /// Emit the first item of a burst immediately and suppress all
+    /// subsequent items until the input stream has been quiet for the delay.
+    Leading,
+
+    /// Emit the last item of a burst after the input stream has been quiet
+    /// for the delay.
+    Trailing,
+
+    /// Emit both the first and the last item of a burst.
+    ///
+    /// The last item is only emitted if it differs from the first item,
+    /// i.e. if the burst consists of more than a single item.
+    Both,
+}
+
+impl DebounceEdge {
+    const fn is_leading(self) -> bool {
+        matches!(self, Self::Leading | Self::Both)
+    }
+
+    const fn is_trailing(self) -> bool {
+        matches!(self, Self::Trailing | Self::Both)
+    }
+}
+
+/// Configuration of [`Debounced`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DebounceConfig {
+    /// Debounce delay
+    ///
+    /// The quiet period without new items that ends a burst.
+    pub delay: Duration,
+
+    /// Debounce edge gate
+    ///
+    /// Controls whether the first and/or the last item of a burst is emitted.
+    pub edge: DebounceEdge,
+
+    /// Maximum duration of a burst
+    ///
+    /// Ends a burst after this duration has passed since the burst began,
+    /// even if new items keep arriving. The last item is then emitted on
+    /// the trailing edge and the next item starts a new burst. Guarantees
+    /// progress for input streams that never become quiet.
+    ///
+    /// Bursts are unlimited if `None`.
+    pub max_wait: Option<Duration>,
+}
+
+impl From<Duration> for DebounceConfig {
+    /// Debounces on the trailing edge.
+    fn from(delay: Duration) -> Self {
+        Self {
+            delay,
+            edge: DebounceEdge::Trailing,
+            max_wait: None,
+        }
+    }
+}
+
+/// Outcome of receiving an item during a burst
+pub(crate) enum Received<T> {
+    /// The item has been discarded and the delay continues.
+    Ignored,
+
+    /// The item has been received into the pending value and restarts the delay.
+    Debounced,
+
+    /// The burst must end immediately.
+    ///
+    /// The pending value is emitted before the given value.
+    Flushed(T),
+}
+
+/// Receives the items of a burst into a pending value.
+pub(crate) trait BurstHandler<I> {
+    /// Pending value of a burst.
+    type Pending;
+
+    /// Receives the next item of the input stream.
+    fn receive(&mut self, pending: &mut Option<Self::Pending>, item: I) -> Received<Self::Pending>;
+
+    /// Ends a burst after the delay or the maximum wait time has elapsed.
+    ///
+    /// Returns the value that is emitted on the trailing edge.
+    fn end_burst(&mut self, pending: Option<Self::Pending>) -> Option<Self::Pending> {
+        pending
+    }
+}
+
+/// Replaces the pending value with each received item.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReplacePending;
+
+impl<I> BurstHandler<I> for ReplacePending {
+    type Pending = I;
+
+    fn receive(&mut self, pending: &mut Option<I>, item: I) -> Received<I> {
+        *pending = Some(item);
+        Received::Debounced
+    }
+}
 
 pin_project! {
+    /// Shared state and implementation of the debounced streams with
+    /// a single pending value.
+    ///
+    /// Used by [`Debounced`], [`DebouncedBy`], [`DebouncedWith`],
+    /// [`DebouncedFold`], [`DebouncedFlushIf`], [`DebouncedDistinct`],
+    /// [`AdaptiveDebounced`](self::adaptive::AdaptiveDebounced),
+    /// [`SessionWindows`](self::session::SessionWindows), and
+    /// [`StableFor`](crate::StableFor).
+    /// The items of a burst are received into the pending value by
+    /// a [`BurstHandler`].
+    ///
+    /// The delay of a burst is an arbitrary future that is restarted for the
+    /// most recently received value.
     #[derive(Debug)]
-    #[project = DelayedProjected]
-    struct Delayed<T, S: Sleep> {
-        output: Option<T>,
+    #[project = DebounceStateProjected]
+    pub(crate) struct DebounceState<St, T, D, W> {
+        #[pin]
+        stream: Option<St>,
+
+        ready_budget: ReadyBudget,
 
+        // Active during a burst. Kept after it has elapsed to be restarted
+        // by the next burst.
         #[pin]
-        sleep: S,
+        delay: Option<D>,
+
+        // Active during a burst if a maximum wait time has been configured.
+        #[pin]
+        max_wait: Option<W>,
+
+        in_burst: bool,
+
+        // The value of the current burst on the trailing edge.
+        pending: Option<T>,
+
+        // Emitted after the pending value when a burst has ended prematurely.
+        flushed: Option<T>,
     }
 }
 
-impl<T, S: Sleep> Delayed<T, S> {
-    pub(crate) fn new(output: T, delay: Duration) -> Self {
+impl<St, T, D, W> DebounceState<St, T, D, W>
+where
+    St: Stream,
+    D: Future,
+    W: Future,
+{
+    pub(crate) const fn new(stream: St, ready_budget: ReadyBudget) -> Self {
         Self {
-            output: Some(output),
-            sleep: S::sleep(delay),
+            stream: Some(stream),
+            ready_budget,
+            delay: None,
+            max_wait: None,
+            in_burst: false,
+            pending: None,
+            flushed: None,
         }
     }
-}
 
-impl<T, S: Sleep> Future for Delayed<T, S> {
-    type Output = T;
+    /// Polls the next value.
+    ///
+    /// Receives items with `handler`. Restarts the delay for the most
+    /// recently received value with `restart_delay` and the maximum wait
+    /// time of a new burst with `restart_max_wait`.
+    pub(crate) fn poll_next<H>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        edge: DebounceEdge,
+        handler: &mut H,
+        mut restart_delay: impl FnMut(Pin<&mut Option<D>>, &T),
+        restart_max_wait: impl FnOnce(Pin<&mut Option<W>>),
+    ) -> Poll<Option<T>>
+    where
+        H: BurstHandler<St::Item, Pending = T>,
+    {
+        let DebounceStateProjected {
+            mut stream,
+            ready_budget,
+            mut delay,
+            mut max_wait,
+            in_burst,
+            pending,
+            flushed,
+        } = self.project();
+
+        if flushed.is_none()
+            && let Some(poll_stream) = stream.as_mut().as_pin_mut()
+        {
+            let was_in_burst = *in_burst;
+            let mut leading = None;
+            // Received on the leading edge after the first item.
+            let mut discarded = None;
+            let mut received = false;
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let DelayedProjected { output, sleep } = self.project();
-        ready!(sleep.poll(cx));
-        let output = output
-            .take()
-            .expect("future must not be polled again after ready");
-        Poll::Ready(output)
+            // Stop receiving items after a flush to preserve the order.
+            let ready_items =
+                ready_budget.try_poll_ready_items(poll_stream, cx, |_cx, next_item| {
+                    let target = if !was_in_burst && !received && edge.is_leading() {
+                        &mut leading
+                    } else if edge.is_trailing() {
+                        &mut *pending
+                    } else {
+                        &mut discarded
+                    };
+                    match handler.receive(target, next_item) {
+                        Received::Ignored => ControlFlow::Continue(()),
+                        Received::Debounced => {
+                            received = true;
+                            ControlFlow::Continue(())
+                        }
+                        Received::Flushed(value) => {
+                            *flushed = Some(value);
+                            ControlFlow::Break(())
+                        }
+                    }
+                });
+            if ready_items == ReadyItems::Finished {
+                // Stream has finished and must not be polled again.
+                stream.set(None);
+            }
+
+            if received
+                && let Some(received_value) =
+                    pending.as_ref().or(discarded.as_ref()).or(leading.as_ref())
+            {
+                restart_delay(delay.as_mut(), received_value);
+                if !was_in_burst {
+                    // A new burst has begun.
+                    restart_max_wait(max_wait.as_mut());
+                }
+                *in_burst = true;
+            }
+
+            if let Some(leading) = leading {
+                // The delay is polled on the next invocation.
+                return Poll::Ready(Some(leading));
+            }
+        }
+
+        if flushed.is_some() {
+            if *in_burst {
+                // The burst has ended prematurely and its timers are canceled.
+                *in_burst = false;
+                delay.set(None);
+                max_wait.set(None);
+            }
+            let next_value = pending.take().or_else(|| flushed.take());
+            return Poll::Ready(next_value);
+        }
+
+        if *in_burst {
+            let mut delay_elapsed = false;
+            let mut max_wait_elapsed = false;
+            if stream.is_some() || pending.is_some() {
+                // Poll both delays to get woken up by either of them.
+                let poll_delay = delay.as_mut().as_pin_mut().expect("delay during burst");
+                delay_elapsed = poll_delay.poll(cx).is_ready();
+                max_wait_elapsed = max_wait
+                    .as_mut()
+                    .as_pin_mut()
+                    .is_some_and(|poll_max_wait| poll_max_wait.poll(cx).is_ready());
+                if !delay_elapsed && !max_wait_elapsed {
+                    return Poll::Pending;
+                }
+            }
+            // The burst has ended. Only timers that have elapsed are kept.
+            *in_burst = false;
+            if !delay_elapsed {
+                delay.set(None);
+            }
+            if !max_wait_elapsed {
+                max_wait.set(None);
+            }
+            if let Some(value) = handler.end_burst(pending.take()) {
+                return Poll::Ready(Some(value));
+            }
+        }
+
+        if stream.is_none() {
+            // Stream has finished.
+            Poll::Ready(None)
+        } else {
+            // New stream items may arrive later.
+            Poll::Pending
+        }
+    }
+}
+
+impl<St, T, S> DebounceState<St, T, S, S>
+where
+    St: Stream,
+    S: Sleep,
+{
+    /// Polls the next value with the timers of a configuration.
+    pub(crate) fn poll_next_config<H>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        config: &DebounceConfig,
+        handler: &mut H,
+    ) -> Poll<Option<T>>
+    where
+        H: BurstHandler<St::Item, Pending = T>,
+    {
+        let DebounceConfig {
+            delay,
+            edge,
+            max_wait,
+        } = *config;
+        self.poll_next(
+            cx,
+            edge,
+            handler,
+            |delay_sleep, _| restart_sleep(delay_sleep, delay),
+            |max_wait_sleep| {
+                if let Some(max_wait) = max_wait {
+                    restart_sleep(max_wait_sleep, max_wait);
+                }
+            },
+        )
     }
 }
 
 pin_project! {
     /// Result of [`StreamExt::debounce()`](crate::StreamExt::debounce).
     #[derive(Debug)]
-    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
     #[project = DebouncedProjected]
     pub struct Debounced<St: Stream, S: Sleep> {
         #[pin]
-        stream: Option<St>,
+        state: DebounceState<St, St::Item, S, S>,
 
-        delay: Duration,
-
-        #[pin]
-        pending: Option<Delayed<St::Item, S>>,
+        config: DebounceConfig,
     }
 }
 
 impl<St: Stream, S: Sleep> Debounced<St, S> {
-    pub(crate) const fn new(stream: St, delay: Duration) -> Self {
+    pub(crate) const fn new(
+        stream: St,
+        config: DebounceConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self::with_ready_budget(
+            stream,
+            config,
+            ReadyBudget::MaxCount(poll_next_max_ready_count),
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) const fn with_coop_budget(stream: St, config: DebounceConfig) -> Self {
+        Self::with_ready_budget(stream, config, ReadyBudget::TokioCoop)
+    }
+
+    const fn with_ready_budget(
+        stream: St,
+        config: DebounceConfig,
+        ready_budget: ReadyBudget,
+    ) -> Self {
         Self {
-            stream: Some(stream),
-            delay,
-            pending: None,
+            state: DebounceState::new(stream, ready_budget),
+            config,
         }
     }
 }
@@ -79,48 +428,64 @@ where
     type Item = St::Item;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let DebouncedProjected {
-            delay,
-            mut stream,
-            mut pending,
-        } = self.project();
+        let DebouncedProjected { state, config } = self.project();
+        state.poll_next_config(cx, config, &mut ReplacePending)
+    }
+}
 
-        if let Some(mut poll_stream) = stream.as_mut().as_pin_mut() {
-            let mut last_item = None;
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        task::{Context, Poll},
+        time::Duration,
+    };
 
-            while let Poll::Ready(next_item) = poll_stream.as_mut().poll_next(cx) {
-                if let Some(next_item) = next_item {
-                    last_item = Some(next_item);
-                    // Continue polling the stream while ready.
-                    continue;
-                }
-                // Stream has finished and must not be polled again.
-                stream.set(None);
-                break;
-            }
+    use futures::{StreamExt as _, stream, task::noop_waker_ref};
 
-            // Replace pending with delayed last item from the stream.
-            if let Some(last_item) = last_item {
-                let next_pending = Delayed::new(last_item, *delay);
-                // The currently pending future is canceled and dropped by overwriting it.
-                pending.set(Some(next_pending));
-            }
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualClock, VirtualSleep},
+    };
+
+    use super::{DebounceConfig, DebounceEdge};
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    fn config(edge: DebounceEdge) -> DebounceConfig {
+        DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge,
+            max_wait: None,
         }
+    }
+
+    #[test]
+    fn trailing_edge_should_emit_the_last_item_of_a_burst_received_in_a_single_poll() {
+        let clock = VirtualClock::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let mut debounced = stream::iter([1, 2, 3]).debounce::<VirtualSleep>(
+            config(DebounceEdge::Trailing),
+            NonZeroUsize::new(10).unwrap(),
+        );
+
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+        clock.advance(TIME_TICK.saturating_mul(10));
+        assert_eq!(Poll::Ready(Some(3)), debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Ready(None), debounced.poll_next_unpin(&mut cx));
+    }
+
+    #[test]
+    fn both_edges_should_emit_the_first_and_last_item_of_a_burst_received_in_a_single_poll() {
+        let clock = VirtualClock::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let mut debounced = stream::iter([1, 2, 3])
+            .debounce::<VirtualSleep>(config(DebounceEdge::Both), NonZeroUsize::new(10).unwrap());
 
-        let Some(poll_pending) = pending.as_mut().as_pin_mut() else {
-            // No pending item.
-            return if stream.is_none() {
-                // Stream has finished.
-                Poll::Ready(None)
-            } else {
-                // New stream items may arrive later.
-                Poll::Pending
-            };
-        };
-
-        let item = ready!(poll_pending.poll(cx));
-        // The future must not be polled again after it became ready.
-        pending.set(None);
-        Poll::Ready(Some(item))
+        assert_eq!(Poll::Ready(Some(1)), debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+        clock.advance(TIME_TICK.saturating_mul(10));
+        assert_eq!(Poll::Ready(Some(3)), debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Ready(None), debounced.poll_next_unpin(&mut cx));
     }
 }
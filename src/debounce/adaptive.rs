@@ -0,0 +1,197 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::VecDeque;
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Clock, budget::ReadyBudget};
+
+use super::{BurstHandler, DebounceConfig, DebounceEdge, DebounceState, Received};
+
+/// Configuration of [`AdaptiveDebounced`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdaptiveDebounceConfig {
+    /// Debounce delay
+    ///
+    /// The quiet period that ends a burst while debouncing.
+    pub delay: Duration,
+
+    /// Maximum number of items per rate window that are passed through
+    ///
+    /// Debouncing starts when more items arrive within a rate window.
+    pub rate_threshold: NonZeroUsize,
+
+    /// Duration of the rate window
+    ///
+    /// The rate window slides with the arrival of each item and covers
+    /// all items that have arrived within this duration before.
+    pub rate_window: Duration,
+}
+
+/// Passes items through while the arrival rate stays below the threshold.
+#[derive(Debug)]
+struct PassBelowRate<S: Clock> {
+    rate_threshold: NonZeroUsize,
+
+    rate_window: Duration,
+
+    // Arrival times of the most recent items within the rate window,
+    // limited to the rate threshold.
+    arrivals: VecDeque<S::Instant>,
+}
+
+impl<I, S: Clock> BurstHandler<I> for PassBelowRate<S> {
+    type Pending = I;
+
+    fn receive(&mut self, pending: &mut Option<I>, item: I) -> Received<I> {
+        let Self {
+            rate_threshold,
+            rate_window,
+            arrivals,
+        } = self;
+        let now = S::now();
+        // Forget arrivals that have left the sliding rate window.
+        while arrivals.front().is_some_and(|&arrived_at| {
+            S::saturating_duration_since(now, arrived_at) >= *rate_window
+        }) {
+            arrivals.pop_front();
+        }
+        let above_threshold = arrivals.len() >= rate_threshold.get();
+        if above_threshold {
+            arrivals.pop_front();
+        }
+        arrivals.push_back(now);
+        if pending.is_none() && !above_threshold {
+            // Pass through and stop receiving items to preserve the order.
+            return Received::Flushed(item);
+        }
+        // The arrival rate is above the threshold or a burst is being debounced.
+        *pending = Some(item);
+        Received::Debounced
+    }
+
+    fn end_burst(&mut self, pending: Option<I>) -> Option<I> {
+        // Pass through items again until the arrival rate exceeds the threshold.
+        self.arrivals.clear();
+        pending
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_adaptive()`](crate::StreamExt::debounce_adaptive).
+    #[derive(Debug)]
+    #[project = AdaptiveDebouncedProjected]
+    pub struct AdaptiveDebounced<St: Stream, S: Clock> {
+        #[pin]
+        state: DebounceState<St, St::Item, S, S>,
+
+        config: AdaptiveDebounceConfig,
+
+        rate: PassBelowRate<S>,
+    }
+}
+
+impl<St, S> AdaptiveDebounced<St, S>
+where
+    St: Stream,
+    S: Clock,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: AdaptiveDebounceConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            config,
+            rate: PassBelowRate {
+                rate_threshold: config.rate_threshold,
+                rate_window: config.rate_window,
+                arrivals: VecDeque::new(),
+            },
+        }
+    }
+}
+
+impl<St, S> Stream for AdaptiveDebounced<St, S>
+where
+    St: Stream,
+    S: Clock,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let AdaptiveDebouncedProjected {
+            state,
+            config: AdaptiveDebounceConfig { delay, .. },
+            rate,
+        } = self.project();
+        let config = DebounceConfig {
+            delay: *delay,
+            edge: DebounceEdge::Trailing,
+            max_wait: None,
+        };
+        state.poll_next_config(cx, &config, rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    use super::AdaptiveDebounceConfig;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn debounce_adaptive_should_only_debounce_above_the_rate_threshold() {
+        let config = AdaptiveDebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            rate_threshold: NonZeroUsize::new(2).unwrap(),
+            rate_window: TIME_TICK.saturating_mul(20),
+        };
+
+        // ms:   0 | 30 | 32 | 34 | 36 | 50
+        // item: 0 |  1 |  2 |  3 |  4 |  5
+        let timed_items = [(0, 0), (30, 1), (32, 2), (34, 3), (36, 4), (50, 5)];
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.debounce_adaptive::<VirtualSleep>(config, NonZeroUsize::MIN)
+        });
+
+        assert_eq!(vec![(0, 0), (30, 1), (32, 2), (46, 4), (50, 5)], output);
+    }
+
+    #[test]
+    fn debounce_adaptive_should_count_items_within_a_sliding_window() {
+        let config = AdaptiveDebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            rate_threshold: NonZeroUsize::new(2).unwrap(),
+            rate_window: TIME_TICK.saturating_mul(20),
+        };
+
+        // The burst crosses the boundary between two consecutive windows
+        // that start at 0 ms and 20 ms.
+        //
+        // ms:   0 | 19 | 20 | 21
+        // item: 0 |  1 |  2 |  3
+        let timed_items = [(0, 0), (19, 1), (20, 2), (21, 3)];
+        let output = run_timed(timed_items, 40, |rx| {
+            rx.debounce_adaptive::<VirtualSleep>(config, NonZeroUsize::MIN)
+        });
+
+        assert_eq!(vec![(0, 0), (19, 1), (20, 2), (31, 3)], output);
+    }
+}
@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Sleep, budget::ReadyBudget};
+
+use super::{BurstHandler, DebounceConfig, DebounceState, Received};
+
+/// Ignores duplicates of the pending or the previously emitted item.
+#[derive(Debug)]
+struct IgnoreDuplicates<T> {
+    last_emitted: Option<T>,
+}
+
+impl<T: PartialEq> BurstHandler<T> for IgnoreDuplicates<T> {
+    type Pending = T;
+
+    fn receive(&mut self, pending: &mut Option<T>, item: T) -> Received<T> {
+        let unchanged = match pending {
+            Some(pending) => *pending == item,
+            None => self.last_emitted.as_ref() == Some(&item),
+        };
+        if unchanged {
+            // Duplicates neither replace the pending item nor restart the delay.
+            return Received::Ignored;
+        }
+        *pending = Some(item);
+        Received::Debounced
+    }
+
+    fn end_burst(&mut self, pending: Option<T>) -> Option<T> {
+        // The burst may have returned to the previously emitted value.
+        pending.filter(|item| self.last_emitted.as_ref() != Some(item))
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_distinct()`](crate::StreamExt::debounce_distinct).
+    #[derive(Debug)]
+    #[project = DebouncedDistinctProjected]
+    pub struct DebouncedDistinct<St: Stream, S> {
+        #[pin]
+        state: DebounceState<St, St::Item, S, S>,
+
+        config: DebounceConfig,
+
+        distinct: IgnoreDuplicates<St::Item>,
+    }
+}
+
+impl<St, S> DebouncedDistinct<St, S>
+where
+    St: Stream,
+    St::Item: Clone + PartialEq,
+    S: Sleep,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: DebounceConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            config,
+            distinct: IgnoreDuplicates { last_emitted: None },
+        }
+    }
+}
+
+impl<St, S> Stream for DebouncedDistinct<St, S>
+where
+    St: Stream,
+    St::Item: Clone + PartialEq,
+    S: Sleep,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedDistinctProjected {
+            state,
+            config,
+            distinct,
+        } = self.project();
+        let next_item = ready!(state.poll_next_config(cx, config, distinct));
+        if let Some(next_item) = &next_item {
+            distinct.last_emitted = Some(next_item.clone());
+        }
+        Poll::Ready(next_item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        DebounceConfig, DebounceEdge, StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn debounce_distinct_should_neither_restart_nor_repeat_for_duplicates() {
+        // ms:   0..=8 | 12 | 14 | 30 | 40
+        // item:     1 |  2 |  1 |  1 |  3
+        let timed_items =
+            (0..=8)
+                .map(|millis| (millis, 1))
+                .chain([(12, 2), (14, 1), (30, 1), (40, 3)]);
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.debounce_distinct::<VirtualSleep>(TIME_TICK.saturating_mul(10), NonZeroUsize::MIN)
+        });
+
+        assert_eq!(vec![(10, 1), (50, 3)], output);
+    }
+
+    #[test]
+    fn debounce_distinct_should_not_emit_duplicates_on_both_edges() {
+        let config = DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge: DebounceEdge::Both,
+            max_wait: None,
+        };
+
+        // ms:   0 | 2 | 4 | 6 | 30 | 40
+        // item: 1 | 1 | 2 | 2 |  2 |  3
+        let timed_items = [(0, 1), (2, 1), (4, 2), (6, 2), (30, 2), (40, 3)];
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.debounce_distinct::<VirtualSleep>(config, NonZeroUsize::MIN)
+        });
+
+        assert_eq!(vec![(0, 1), (14, 2), (40, 3)], output);
+    }
+}
@@ -0,0 +1,216 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Sleep, budget::ReadyBudget};
+
+use super::{BurstHandler, DebounceConfig, DebounceState, Received};
+
+/// Handling of the pending item when an urgent item arrives
+///
+/// See also: [`StreamExt::debounce_flush_if()`](crate::StreamExt::debounce_flush_if)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlushPolicy {
+    /// Discard the pending item
+    ///
+    /// Only the urgent item is emitted.
+    DiscardPending,
+
+    /// Emit the pending item immediately
+    ///
+    /// The pending item is emitted before the urgent item.
+    EmitPending,
+}
+
+/// Flushes the pending item when an urgent item arrives.
+#[derive(Debug)]
+struct FlushIf<F> {
+    flush_if: F,
+    policy: FlushPolicy,
+}
+
+impl<I, F> BurstHandler<I> for FlushIf<F>
+where
+    F: FnMut(&I) -> bool,
+{
+    type Pending = I;
+
+    fn receive(&mut self, pending: &mut Option<I>, item: I) -> Received<I> {
+        if (self.flush_if)(&item) {
+            if self.policy == FlushPolicy::DiscardPending {
+                *pending = None;
+            }
+            return Received::Flushed(item);
+        }
+        *pending = Some(item);
+        Received::Debounced
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_flush_if()`](crate::StreamExt::debounce_flush_if).
+    #[derive(Debug)]
+    #[project = DebouncedFlushIfProjected]
+    pub struct DebouncedFlushIf<St: Stream, S, F> {
+        #[pin]
+        state: DebounceState<St, St::Item, S, S>,
+
+        config: DebounceConfig,
+
+        flush: FlushIf<F>,
+    }
+}
+
+/// Result of [`StreamExt::debounce_ok()`](crate::StreamExt::debounce_ok).
+pub type DebouncedOk<St, S> = DebouncedFlushIf<St, S, fn(&<St as Stream>::Item) -> bool>;
+
+impl<St, S, F> DebouncedFlushIf<St, S, F>
+where
+    St: Stream,
+    S: Sleep,
+    F: FnMut(&St::Item) -> bool,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: DebounceConfig,
+        flush_if: F,
+        policy: FlushPolicy,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            config,
+            flush: FlushIf { flush_if, policy },
+        }
+    }
+}
+
+impl<St, S, F> Stream for DebouncedFlushIf<St, S, F>
+where
+    St: Stream,
+    S: Sleep,
+    F: FnMut(&St::Item) -> bool,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedFlushIfProjected {
+            state,
+            config,
+            flush,
+        } = self.project();
+        state.poll_next_config(cx, config, flush)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        DebounceConfig, DebounceEdge, StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    use super::FlushPolicy;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    fn run_debounce_flush_if(policy: FlushPolicy) -> Vec<(u128, i32)> {
+        // ms:   0 | 2 |  4 | 20 | 22 | 40
+        // item: 1 | 2 | -1 |  3 | -2 |  4
+        let timed_items = [(0, 1), (2, 2), (4, -1), (20, 3), (22, -2), (40, 4)];
+        run_timed(timed_items, 60, |rx| {
+            rx.debounce_flush_if::<VirtualSleep, _>(
+                TIME_TICK.saturating_mul(10),
+                |item: &i32| item.is_negative(),
+                policy,
+                NonZeroUsize::MIN,
+            )
+        })
+    }
+
+    #[test]
+    fn debounce_ok_should_pass_through_errors_immediately() {
+        // ms:   0     | 2     | 4      | 6
+        // item: Ok(1) | Ok(2) | Err(a) | Ok(3)
+        let timed_items = [(0, Ok(1)), (2, Ok(2)), (4, Err("a")), (6, Ok(3))];
+        let output = run_timed(timed_items, 30, |rx| {
+            rx.debounce_ok::<VirtualSleep, _, _>(
+                TIME_TICK.saturating_mul(10),
+                FlushPolicy::EmitPending,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(4, Ok(2)), (4, Err("a")), (16, Ok(3))], output);
+    }
+
+    #[test]
+    fn debounce_ok_should_start_a_new_burst_after_an_error_on_the_leading_edge() {
+        let config = DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge: DebounceEdge::Leading,
+            max_wait: None,
+        };
+
+        // ms:   0     | 2     | 4      | 6
+        // item: Ok(1) | Ok(2) | Err(a) | Ok(3)
+        let timed_items = [(0, Ok(1)), (2, Ok(2)), (4, Err("a")), (6, Ok(3))];
+        let output = run_timed(timed_items, 30, |rx| {
+            rx.debounce_ok::<VirtualSleep, _, _>(
+                config,
+                FlushPolicy::EmitPending,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(0, Ok(1)), (4, Err("a")), (6, Ok(3))], output);
+    }
+
+    #[test]
+    fn debounce_flush_if_should_emit_the_pending_item_after_the_maximum_wait_time() {
+        let config = DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge: DebounceEdge::Trailing,
+            max_wait: Some(TIME_TICK.saturating_mul(25)),
+        };
+
+        // The input stream never becomes quiet.
+        let timed_items = (0..60).step_by(5).map(|millis| (millis, millis));
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.debounce_flush_if::<VirtualSleep, _>(
+                config,
+                |item: &u64| *item == 40,
+                FlushPolicy::EmitPending,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(25, 25), (40, 35), (40, 40), (65, 55)], output);
+    }
+
+    #[test]
+    fn debounce_flush_if_should_discard_the_pending_item() {
+        assert_eq!(
+            vec![(4, -1), (22, -2), (50, 4)],
+            run_debounce_flush_if(FlushPolicy::DiscardPending)
+        );
+    }
+
+    #[test]
+    fn debounce_flush_if_should_emit_the_pending_item() {
+        assert_eq!(
+            vec![(4, 2), (4, -1), (22, 3), (22, -2), (50, 4)],
+            run_debounce_flush_if(FlushPolicy::EmitPending)
+        );
+    }
+}
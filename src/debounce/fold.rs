@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Sleep, budget::ReadyBudget};
+
+use super::{BurstHandler, DebounceConfig, DebounceState, Received};
+
+/// Folds the items of a burst into an accumulator.
+#[derive(Debug)]
+struct FoldPending<A, F> {
+    init: A,
+    fold_fn: F,
+}
+
+impl<I, A, F> BurstHandler<I> for FoldPending<A, F>
+where
+    A: Clone,
+    F: FnMut(A, I) -> A,
+{
+    type Pending = A;
+
+    fn receive(&mut self, pending: &mut Option<A>, item: I) -> Received<A> {
+        // Each burst starts with the initial value.
+        let acc = pending.take().unwrap_or_else(|| self.init.clone());
+        *pending = Some((self.fold_fn)(acc, item));
+        Received::Debounced
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_fold()`](crate::StreamExt::debounce_fold).
+    #[derive(Debug)]
+    #[project = DebouncedFoldProjected]
+    pub struct DebouncedFold<St, S, A, F> {
+        #[pin]
+        state: DebounceState<St, A, S, S>,
+
+        config: DebounceConfig,
+
+        fold: FoldPending<A, F>,
+    }
+}
+
+impl<St, S, A, F> DebouncedFold<St, S, A, F>
+where
+    St: Stream,
+    S: Sleep,
+    A: Clone,
+    F: FnMut(A, St::Item) -> A,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: DebounceConfig,
+        init: A,
+        fold_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            config,
+            fold: FoldPending { init, fold_fn },
+        }
+    }
+}
+
+impl<St, S, A, F> Stream for DebouncedFold<St, S, A, F>
+where
+    St: Stream,
+    S: Sleep,
+    A: Clone,
+    F: FnMut(A, St::Item) -> A,
+{
+    type Item = A;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedFoldProjected {
+            state,
+            config,
+            fold,
+        } = self.project();
+        state.poll_next_config(cx, config, fold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        DebounceConfig, DebounceEdge, StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn debounce_fold_should_sum_all_items_of_a_burst() {
+        // ms:    0 | 2 | 4 | 30
+        // delta: 1 | 2 | 3 | 10
+        let output = run_timed([(0, 1), (2, 2), (4, 3), (30, 10)], 50, |rx| {
+            rx.debounce_fold::<VirtualSleep, _, _>(
+                TIME_TICK.saturating_mul(10),
+                0,
+                |sum, delta| sum + delta,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(14, 6), (40, 10)], output);
+    }
+
+    #[test]
+    fn debounce_fold_should_fold_the_first_item_of_a_burst_separately_on_both_edges() {
+        let config = DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge: DebounceEdge::Both,
+            max_wait: None,
+        };
+
+        // ms:    0 | 2 | 4 | 30
+        // delta: 1 | 2 | 3 | 10
+        let output = run_timed([(0, 1), (2, 2), (4, 3), (30, 10)], 50, |rx| {
+            rx.debounce_fold::<VirtualSleep, _, _>(
+                config,
+                0,
+                |sum, delta| sum + delta,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(0, 1), (14, 5), (30, 10)], output);
+    }
+
+    #[test]
+    fn debounce_fold_should_emit_the_accumulator_after_the_maximum_wait_time() {
+        let config = DebounceConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            edge: DebounceEdge::Trailing,
+            max_wait: Some(TIME_TICK.saturating_mul(25)),
+        };
+
+        // The input stream never becomes quiet.
+        let timed_items = (0..60).step_by(5).map(|millis| (millis, 1));
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.debounce_fold::<VirtualSleep, _, _>(
+                config,
+                0,
+                |count, one| count + one,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(25, 6), (55, 6)], output);
+    }
+}
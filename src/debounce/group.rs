@@ -0,0 +1,539 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker, ready},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{
+    Clock,
+    budget::{ReadyBudget, ReadyItems},
+    restart_sleep,
+};
+
+/// Registration of a waker in the group.
+type TimerKey = (u64, u64);
+
+#[derive(Debug, Default)]
+struct GroupState {
+    handle_count: usize,
+    next_timer_id: u64,
+    timers: BTreeMap<TimerKey, Waker>,
+    driver_waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+struct GroupShared<I> {
+    started_at: I,
+    resolution: Duration,
+    state: Mutex<GroupState>,
+}
+
+impl<I> GroupShared<I> {
+    fn lock_state(&self) -> MutexGuard<'_, GroupState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Shared timer for many debounced streams.
+///
+/// All streams of a group register their deadlines with a single
+/// [`DebounceGroupDriver`] instead of creating a separate timer
+/// for each pending item. The driver only wakes up at multiples of
+/// the tick resolution and then wakes the streams whose deadlines
+/// have passed. Items are therefore emitted up to one tick resolution
+/// later than with [`Debounced`](crate::Debounced).
+///
+/// The driver must be spawned onto an executor. It finishes after the
+/// group and all of its streams have been dropped.
+#[derive(Debug)]
+pub struct DebounceGroup<S: Clock> {
+    shared: Arc<GroupShared<S::Instant>>,
+}
+
+impl<S: Clock> DebounceGroup<S> {
+    /// Creates a new group and its driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolution` is zero.
+    pub fn new(resolution: Duration) -> (Self, DebounceGroupDriver<S>) {
+        assert!(!resolution.is_zero(), "resolution must not be zero");
+        let shared = Arc::new(GroupShared {
+            started_at: S::now(),
+            resolution,
+            state: Mutex::new(GroupState {
+                handle_count: 1,
+                ..Default::default()
+            }),
+        });
+        let driver = DebounceGroupDriver {
+            shared: Arc::clone(&shared),
+            sleep: None,
+            sleep_tick: None,
+        };
+        (Self { shared }, driver)
+    }
+
+    /// Debounces an input stream with the shared timer of this group.
+    ///
+    /// An item whose quiet period would end beyond the range of the clock
+    /// is only emitted after the input stream has finished.
+    ///
+    /// See also: [`StreamExt::debounce()`](crate::StreamExt::debounce)
+    pub fn debounce<St: Stream>(
+        &self,
+        stream: St,
+        delay: Duration,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> GroupDebounced<St, S> {
+        GroupDebounced {
+            stream: Some(stream),
+            delay,
+            ready_budget: ReadyBudget::MaxCount(poll_next_max_ready_count),
+            pending: None,
+            timer_key: None,
+            group: self.clone(),
+        }
+    }
+
+    /// Rounds the deadline up to the next tick.
+    #[expect(clippy::cast_possible_truncation)]
+    fn deadline_tick(&self, deadline: S::Instant) -> u64 {
+        let elapsed = S::saturating_duration_since(deadline, self.shared.started_at).as_nanos();
+        elapsed.div_ceil(self.shared.resolution.as_nanos()) as u64
+    }
+
+    /// Registers or updates the timer for a deadline.
+    fn register_timer(
+        &self,
+        timer_key: Option<TimerKey>,
+        deadline: S::Instant,
+        waker: &Waker,
+    ) -> TimerKey {
+        let tick = self.deadline_tick(deadline);
+        let mut state = self.shared.lock_state();
+        let timer_key = match timer_key {
+            Some(timer_key) if timer_key.0 == tick => timer_key,
+            timer_key => {
+                if let Some(timer_key) = timer_key {
+                    state.timers.remove(&timer_key);
+                }
+                let timer_id = state.next_timer_id;
+                state.next_timer_id += 1;
+                (tick, timer_id)
+            }
+        };
+        let next_tick = state.timers.first_key_value().map(|((tick, _), _)| *tick);
+        state.timers.insert(timer_key, waker.clone());
+        // The driver needs to reschedule its timer if the new deadline
+        // is earlier than all other deadlines.
+        let driver_waker = if next_tick.is_none_or(|next_tick| tick < next_tick) {
+            state.driver_waker.take()
+        } else {
+            None
+        };
+        drop(state);
+        if let Some(driver_waker) = driver_waker {
+            driver_waker.wake();
+        }
+        timer_key
+    }
+
+    fn deregister_timer(&self, timer_key: TimerKey) {
+        self.shared.lock_state().timers.remove(&timer_key);
+    }
+}
+
+impl<S: Clock> Clone for DebounceGroup<S> {
+    fn clone(&self) -> Self {
+        self.shared.lock_state().handle_count += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<S: Clock> Drop for DebounceGroup<S> {
+    fn drop(&mut self) {
+        let driver_waker = {
+            let mut state = self.shared.lock_state();
+            debug_assert!(state.handle_count > 0);
+            state.handle_count -= 1;
+            if state.handle_count > 0 {
+                return;
+            }
+            state.driver_waker.take()
+        };
+        // Let the driver finish.
+        if let Some(driver_waker) = driver_waker {
+            driver_waker.wake();
+        }
+    }
+}
+
+pin_project! {
+    /// Driver of a [`DebounceGroup`].
+    ///
+    /// Owns the single timer of the group.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    #[project = DebounceGroupDriverProjected]
+    pub struct DebounceGroupDriver<S: Clock> {
+        shared: Arc<GroupShared<S::Instant>>,
+        #[pin]
+        sleep: Option<S>,
+        sleep_tick: Option<u64>,
+    }
+}
+
+impl<S: Clock> Future for DebounceGroupDriver<S> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let DebounceGroupDriverProjected {
+            shared,
+            mut sleep,
+            sleep_tick,
+        } = self.project();
+        let resolution_nanos = shared.resolution.as_nanos();
+        loop {
+            let now = S::now();
+            #[expect(clippy::cast_possible_truncation)]
+            let now_tick = (S::saturating_duration_since(now, shared.started_at).as_nanos()
+                / resolution_nanos) as u64;
+            let (expired_timers, next_tick) = {
+                let mut state = shared.lock_state();
+                if state.handle_count == 0 {
+                    // Release the timer before finishing.
+                    sleep.set(None);
+                    *sleep_tick = None;
+                    return Poll::Ready(());
+                }
+                state.driver_waker = Some(cx.waker().clone());
+                let pending_timers = state.timers.split_off(&(now_tick.saturating_add(1), 0));
+                let expired_timers = std::mem::replace(&mut state.timers, pending_timers);
+                let next_tick = state.timers.first_key_value().map(|((tick, _), _)| *tick);
+                (expired_timers, next_tick)
+            };
+            // Wake up streams after releasing the lock.
+            for waker in expired_timers.into_values() {
+                waker.wake();
+            }
+            let Some(next_tick) = next_tick else {
+                if sleep_tick.take().is_some() {
+                    // Cancel the pending sleep.
+                    sleep.set(None);
+                }
+                return Poll::Pending;
+            };
+            if *sleep_tick != Some(next_tick) {
+                let Some(deadline) = u64::try_from(u128::from(next_tick) * resolution_nanos)
+                    .ok()
+                    .and_then(|nanos| {
+                        S::checked_add(shared.started_at, Duration::from_nanos(nanos))
+                    })
+                else {
+                    // The deadline is not representable and never reached.
+                    return Poll::Pending;
+                };
+                restart_sleep(sleep.as_mut(), S::saturating_duration_since(deadline, now));
+                *sleep_tick = Some(next_tick);
+            }
+            let poll_sleep = sleep.as_mut().as_pin_mut().expect("some");
+            ready!(poll_sleep.poll(cx));
+            // The elapsed sleep is kept to be restarted for the next tick.
+            *sleep_tick = None;
+        }
+    }
+}
+
+pin_project! {
+    /// Result of [`DebounceGroup::debounce()`].
+    #[derive(Debug)]
+    #[must_use = "streams do nothing unless polled or .awaited"]
+    #[project = GroupDebouncedProjected]
+    pub struct GroupDebounced<St: Stream, S: Clock> {
+        #[pin]
+        stream: Option<St>,
+        delay: Duration,
+        ready_budget: ReadyBudget,
+        // `None` deadline if not representable.
+        pending: Option<(St::Item, Option<S::Instant>)>,
+        timer_key: Option<TimerKey>,
+        group: DebounceGroup<S>,
+    }
+
+    impl<St: Stream, S: Clock> PinnedDrop for GroupDebounced<St, S> {
+        fn drop(this: Pin<&mut Self>) {
+            let this = this.project();
+            if let Some(timer_key) = this.timer_key.take() {
+                this.group.deregister_timer(timer_key);
+            }
+        }
+    }
+}
+
+impl<St, S> Stream for GroupDebounced<St, S>
+where
+    St: Stream,
+    S: Clock,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let GroupDebouncedProjected {
+            mut stream,
+            delay,
+            ready_budget,
+            pending,
+            timer_key,
+            group,
+        } = self.project();
+
+        if let Some(poll_stream) = stream.as_mut().as_pin_mut() {
+            let mut last_item = None;
+
+            let ready_items = ready_budget.poll_ready_items(poll_stream, cx, |_cx, next_item| {
+                last_item = Some(next_item);
+            });
+            if ready_items == ReadyItems::Finished {
+                // Stream has finished and must not be polled again.
+                stream.set(None);
+            }
+
+            // Replace the pending item and restart the delay.
+            if let Some(last_item) = last_item {
+                *pending = Some((last_item, S::checked_add(S::now(), *delay)));
+            }
+        }
+
+        let Some((_, deadline)) = pending else {
+            // No pending item.
+            return if stream.is_none() {
+                // Stream has finished.
+                Poll::Ready(None)
+            } else {
+                // New stream items may arrive later.
+                Poll::Pending
+            };
+        };
+
+        let Some(deadline) = *deadline else {
+            if stream.is_some() {
+                // Never ready while new stream items may arrive.
+                return Poll::Pending;
+            }
+            // Stream has finished. The item would never be emitted otherwise.
+            let (item, _) = pending.take().expect("some");
+            return Poll::Ready(Some(item));
+        };
+
+        if S::now() < deadline {
+            *timer_key = Some(group.register_timer(timer_key.take(), deadline, cx.waker()));
+            return Poll::Pending;
+        }
+
+        if let Some(timer_key) = timer_key.take() {
+            group.deregister_timer(timer_key);
+        }
+        let (item, _) = pending.take().expect("some");
+        Poll::Ready(Some(item))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        num::NonZeroUsize,
+        rc::Rc,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use futures::{
+        FutureExt as _, StreamExt as _,
+        channel::mpsc,
+        executor::LocalPool,
+        task::{LocalSpawnExt as _, noop_waker_ref},
+    };
+
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualClock, VirtualSleep, run_timed},
+    };
+
+    use super::DebounceGroup;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    /// Debounces a single stream in a group that is driven alongside.
+    fn run_timed_grouped(
+        resolution: Duration,
+        delay: Duration,
+        timed_items: impl IntoIterator<Item = (u64, i32)>,
+        end_millis: u64,
+    ) -> Vec<(u128, i32)> {
+        run_timed(timed_items, end_millis, |rx| {
+            let (group, driver) = DebounceGroup::<VirtualSleep>::new(resolution);
+            rx.debounce_grouped(&group, delay, NonZeroUsize::MIN)
+                .take_until(driver)
+        })
+    }
+
+    #[test]
+    fn should_debounce_like_a_separate_timer_with_the_finest_resolution() {
+        // ms:   0 | 2 | 4 | 30
+        // item: 0 | 1 | 2 |  3
+        let timed_items = [(0, 0), (2, 1), (4, 2), (30, 3)];
+        assert_eq!(
+            vec![(14, 2), (40, 3)],
+            run_timed_grouped(TIME_TICK, TIME_TICK.saturating_mul(10), timed_items, 50)
+        );
+    }
+
+    #[test]
+    fn should_debounce_all_streams_with_a_single_timer() {
+        let clock = VirtualClock::new();
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+
+        let (group, driver) = DebounceGroup::<VirtualSleep>::new(TIME_TICK.saturating_mul(5));
+        let driver_finished = Rc::new(RefCell::new(false));
+        spawner
+            .spawn_local({
+                let driver_finished = Rc::clone(&driver_finished);
+                driver.map(move |()| *driver_finished.borrow_mut() = true)
+            })
+            .unwrap();
+
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let senders = ["a", "b", "c"].map(|name| {
+            let (tx, rx) = mpsc::unbounded();
+            let clock = clock.clone();
+            let output = Rc::clone(&output);
+            spawner
+                .spawn_local(
+                    rx.debounce_grouped(&group, TIME_TICK.saturating_mul(10), NonZeroUsize::MIN)
+                        .for_each(move |item| {
+                            output
+                                .borrow_mut()
+                                .push((clock.elapsed().as_millis(), name, item));
+                            async {}
+                        }),
+                )
+                .unwrap();
+            tx
+        });
+        drop(group);
+
+        for millis in 0..30 {
+            match millis {
+                0 => {
+                    senders[0].unbounded_send(0).unwrap();
+                    senders[1].unbounded_send(0).unwrap();
+                }
+                3 => {
+                    senders[2].unbounded_send(0).unwrap();
+                }
+                7 => {
+                    // Replaces the pending item.
+                    senders[0].unbounded_send(1).unwrap();
+                }
+                _ => (),
+            }
+            pool.run_until_stalled();
+            assert!(clock.pending_timer_count() <= 1);
+            clock.advance(TIME_TICK);
+        }
+        pool.run_until_stalled();
+
+        // Deadlines are rounded up to the next multiple of the resolution.
+        assert_eq!(
+            vec![(10, "b", 0), (15, "c", 0), (20, "a", 1)],
+            *output.borrow()
+        );
+
+        assert!(!*driver_finished.borrow());
+        drop(senders);
+        pool.run_until_stalled();
+        assert!(*driver_finished.borrow());
+    }
+
+    #[test]
+    fn should_round_deadlines_up_to_the_next_multiple_of_the_resolution() {
+        // ms:       0 | 11 | 23
+        // item:     0 |  1 |  2
+        // deadline: 7 | 18 | 30
+        let timed_items = [(0, 0), (11, 1), (23, 2)];
+        assert_eq!(
+            vec![(10, 0), (20, 1), (30, 2)],
+            run_timed_grouped(
+                TIME_TICK.saturating_mul(5),
+                TIME_TICK.saturating_mul(7),
+                timed_items,
+                40
+            )
+        );
+    }
+
+    #[test]
+    fn should_emit_an_item_without_a_deadline_when_finished() {
+        // The deadline of the item is not representable.
+        //
+        // ms:   1 | 2
+        // item: 0 | 1
+        let timed_items = [(1, 0), (2, 1)];
+        assert_eq!(
+            vec![(5, 1)],
+            run_timed_grouped(TIME_TICK, Duration::MAX, timed_items, 5)
+        );
+    }
+
+    #[test]
+    fn should_deregister_the_timer_when_dropping_a_stream() {
+        let _clock = VirtualClock::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let (group, _driver) = DebounceGroup::<VirtualSleep>::new(TIME_TICK);
+        let (tx, rx) = mpsc::unbounded();
+        let mut debounced = group.debounce(rx, TIME_TICK.saturating_mul(10), NonZeroUsize::MIN);
+
+        tx.unbounded_send(0).unwrap();
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+        assert_eq!(1, group.shared.lock_state().timers.len());
+
+        drop(debounced);
+        assert!(group.shared.lock_state().timers.is_empty());
+    }
+
+    #[test]
+    fn driver_should_finish_after_all_handles_have_been_dropped() {
+        let clock = VirtualClock::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let (group, mut driver) = DebounceGroup::<VirtualSleep>::new(TIME_TICK);
+        let (tx, rx) = mpsc::unbounded();
+        let mut debounced = group.debounce(rx, TIME_TICK.saturating_mul(10), NonZeroUsize::MIN);
+        tx.unbounded_send(0).unwrap();
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Pending, driver.poll_unpin(&mut cx));
+
+        drop(group);
+        assert_eq!(Poll::Pending, driver.poll_unpin(&mut cx));
+
+        // The stream has been dropped with a pending item and a registered timer.
+        drop(debounced);
+        assert_eq!(Poll::Ready(()), driver.poll_unpin(&mut cx));
+        assert_eq!(0, clock.pending_timer_count());
+    }
+}
@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::collections::{BTreeMap, VecDeque};
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{
+    Clock,
+    budget::{ReadyBudget, ReadyItems},
+    restart_sleep,
+};
+
+/// Configuration of [`DebouncedByKey`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DebounceByKeyConfig {
+    /// Debounce delay
+    ///
+    /// The quiet period of each key.
+    ///
+    /// Items whose quiet period would end beyond the range of the clock
+    /// are only emitted after the input stream has finished.
+    pub delay: Duration,
+
+    /// Maximum number of keys with a pending item
+    ///
+    /// If a new key arrives when this number has been reached, the pending
+    /// item with the earliest deadline is evicted and emitted immediately.
+    pub max_keys: NonZeroUsize,
+}
+
+/// Position of a pending item in the deadline queue.
+///
+/// The sequence number disambiguates equal deadlines.
+type DeadlineKey<I> = (I, u64);
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_by_key()`](crate::StreamExt::debounce_by_key).
+    #[derive(Debug)]
+    #[project = DebouncedByKeyProjected]
+    pub struct DebouncedByKey<St: Stream, S: Clock, K, F> {
+        #[pin]
+        stream: Option<St>,
+
+        config: DebounceByKeyConfig,
+
+        key_fn: F,
+
+        ready_budget: ReadyBudget,
+
+        // The pending item of each key and its position in the deadline queue.
+        //
+        // Items without a representable deadline are never ready.
+        pending: BTreeMap<K, (Option<DeadlineKey<S::Instant>>, St::Item)>,
+
+        deadlines: BTreeMap<DeadlineKey<S::Instant>, K>,
+
+        next_seq: u64,
+
+        // Items that have been evicted and are emitted immediately.
+        evicted: VecDeque<St::Item>,
+
+        // A single timer for the earliest deadline.
+        #[pin]
+        sleep: Option<S>,
+
+        sleep_deadline: Option<S::Instant>,
+    }
+}
+
+impl<St, S, K, F> DebouncedByKey<St, S, K, F>
+where
+    St: Stream,
+    S: Clock,
+    K: Ord + Clone,
+    F: FnMut(&St::Item) -> K,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: DebounceByKeyConfig,
+        key_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            stream: Some(stream),
+            config,
+            key_fn,
+            ready_budget: ReadyBudget::MaxCount(poll_next_max_ready_count),
+            pending: BTreeMap::new(),
+            deadlines: BTreeMap::new(),
+            next_seq: 0,
+            evicted: VecDeque::new(),
+            sleep: None,
+            sleep_deadline: None,
+        }
+    }
+}
+
+impl<St, S, K, F> Stream for DebouncedByKey<St, S, K, F>
+where
+    St: Stream,
+    S: Clock,
+    K: Ord + Clone,
+    F: FnMut(&St::Item) -> K,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedByKeyProjected {
+            mut stream,
+            config: DebounceByKeyConfig { delay, max_keys },
+            key_fn,
+            ready_budget,
+            pending,
+            deadlines,
+            next_seq,
+            evicted,
+            mut sleep,
+            sleep_deadline,
+        } = self.project();
+
+        // Emit evicted items first. This also bounds their number by the
+        // maximum number of ready items that are received per poll.
+        if let Some(item) = evicted.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+
+        if let Some(poll_stream) = stream.as_mut().as_pin_mut() {
+            let ready_items = ready_budget.poll_ready_items(poll_stream, cx, |_cx, next_item| {
+                let key = key_fn(&next_item);
+                let deadline_key = S::checked_add(S::now(), *delay).map(|deadline| {
+                    let seq = *next_seq;
+                    *next_seq = next_seq.wrapping_add(1);
+                    (deadline, seq)
+                });
+                if let Some((old_deadline_key, _)) = pending.remove(&key) {
+                    // Restart the quiet period of this key.
+                    if let Some(old_deadline_key) = old_deadline_key {
+                        deadlines.remove(&old_deadline_key);
+                    }
+                } else if pending.len() >= max_keys.get() {
+                    // Evict the key with the earliest deadline.
+                    let evicted_key = deadlines.pop_first().map_or_else(
+                        || pending.first_key_value().expect("not empty").0.clone(),
+                        |(_, evicted_key)| evicted_key,
+                    );
+                    let (_, evicted_item) = pending.remove(&evicted_key).expect("pending");
+                    evicted.push_back(evicted_item);
+                }
+                if let Some(deadline_key) = deadline_key {
+                    deadlines.insert(deadline_key, key.clone());
+                }
+                pending.insert(key, (deadline_key, next_item));
+            });
+            if ready_items == ReadyItems::Finished {
+                // Stream has finished and must not be polled again.
+                stream.set(None);
+            }
+
+            if let Some(item) = evicted.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+        }
+
+        loop {
+            let Some(&(deadline, seq)) = deadlines.keys().next() else {
+                // No pending items with a deadline.
+                if sleep_deadline.take().is_some() {
+                    // Cancel the pending sleep.
+                    sleep.set(None);
+                }
+                if stream.is_some() {
+                    // New stream items may arrive later.
+                    return Poll::Pending;
+                }
+                // Stream has finished. Items without a representable deadline
+                // would never be emitted otherwise.
+                return Poll::Ready(pending.pop_first().map(|(_, (_, item))| item));
+            };
+
+            let now = S::now();
+            if deadline <= now {
+                // The quiet period of this key has ended.
+                let key = deadlines.remove(&(deadline, seq)).expect("deadline");
+                let (_, item) = pending.remove(&key).expect("pending");
+                return Poll::Ready(Some(item));
+            }
+
+            if *sleep_deadline != Some(deadline) {
+                // The currently pending sleep is restarted for the earliest deadline.
+                restart_sleep(sleep.as_mut(), S::saturating_duration_since(deadline, now));
+                *sleep_deadline = Some(deadline);
+            }
+            let poll_sleep = sleep.as_mut().as_pin_mut().expect("some");
+            ready!(poll_sleep.poll(cx));
+            // The elapsed sleep is kept to be restarted for the next deadline.
+            *sleep_deadline = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    use super::DebounceByKeyConfig;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn debounce_by_key() {
+        let config = DebounceByKeyConfig {
+            delay: TIME_TICK.saturating_mul(10),
+            max_keys: NonZeroUsize::new(2).unwrap(),
+        };
+
+        // ms:   0 | 2 | 4 | 6 | 30
+        // key:  a | b | a | c |  a
+        // item: 0 | 1 | 2 | 3 |  4
+        let timed_items = [
+            (0, ("a", 0)),
+            (2, ("b", 1)),
+            (4, ("a", 2)),
+            (6, ("c", 3)),
+            (30, ("a", 4)),
+        ];
+        let output = run_timed(timed_items, 50, |rx| {
+            rx.debounce_by_key::<VirtualSleep, _, _>(
+                config,
+                |(key, _): &(&str, usize)| *key,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(
+            vec![
+                // Evicted when the third key arrived.
+                (6, ("b", 1)),
+                (14, ("a", 2)),
+                (16, ("c", 3)),
+                (40, ("a", 4)),
+            ],
+            output
+        );
+    }
+
+    #[test]
+    fn debounce_by_key_should_emit_items_without_a_deadline_when_finished() {
+        let config = DebounceByKeyConfig {
+            delay: Duration::MAX,
+            max_keys: NonZeroUsize::new(2).unwrap(),
+        };
+
+        // The deadlines of all items are not representable.
+        //
+        // ms:   1 | 2
+        // key:  b | a
+        // item: 0 | 1
+        let timed_items = [(1, ("b", 0)), (2, ("a", 1))];
+        let output = run_timed(timed_items, 5, |rx| {
+            rx.debounce_by_key::<VirtualSleep, _, _>(
+                config,
+                |(key, _): &(&str, usize)| *key,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(5, ("a", 1)), (5, ("b", 0))], output);
+    }
+}
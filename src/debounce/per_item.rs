@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Sleep, budget::ReadyBudget, restart_sleep};
+
+use super::{DebounceEdge, DebounceState, ReplacePending};
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_by()`](crate::StreamExt::debounce_by).
+    #[derive(Debug)]
+    #[project = DebouncedByProjected]
+    pub struct DebouncedBy<St: Stream, S: Sleep, F> {
+        #[pin]
+        state: DebounceState<St, St::Item, S, S>,
+
+        delay_fn: F,
+    }
+}
+
+impl<St, S, F> DebouncedBy<St, S, F>
+where
+    St: Stream,
+    S: Sleep,
+    F: FnMut(&St::Item) -> Duration,
+{
+    pub(crate) const fn new(
+        stream: St,
+        delay_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            delay_fn,
+        }
+    }
+}
+
+impl<St, S, F> Stream for DebouncedBy<St, S, F>
+where
+    St: Stream,
+    S: Sleep,
+    F: FnMut(&St::Item) -> Duration,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedByProjected { state, delay_fn } = self.project();
+        state.poll_next(
+            cx,
+            DebounceEdge::Trailing,
+            &mut ReplacePending,
+            |delay, item| restart_sleep(delay, delay_fn(item)),
+            |_| (),
+        )
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::debounce_with()`](crate::StreamExt::debounce_with).
+    #[derive(Debug)]
+    #[project = DebouncedWithProjected]
+    pub struct DebouncedWith<St: Stream, F, Fut: Future> {
+        #[pin]
+        state: DebounceState<St, St::Item, Fut, Fut>,
+
+        delay_fn: F,
+    }
+}
+
+impl<St, F, Fut> DebouncedWith<St, F, Fut>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> Fut,
+    Fut: Future,
+{
+    pub(crate) const fn new(
+        stream: St,
+        delay_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            delay_fn,
+        }
+    }
+}
+
+impl<St, F, Fut> Stream for DebouncedWith<St, F, Fut>
+where
+    St: Stream,
+    F: FnMut(&St::Item) -> Fut,
+    Fut: Future,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let DebouncedWithProjected { state, delay_fn } = self.project();
+        state.poll_next(
+            cx,
+            DebounceEdge::Trailing,
+            &mut ReplacePending,
+            |mut delay, item| delay.set(Some(delay_fn(item))),
+            |_| (),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        pin::pin,
+        task::{Context, Poll},
+    };
+
+    use futures::{
+        FutureExt as _, StreamExt as _,
+        channel::{mpsc, oneshot},
+        task::noop_waker_ref,
+    };
+
+    use crate::StreamExt as _;
+
+    #[test]
+    fn debounce_with_should_delay_the_pending_item_until_the_future_completes() {
+        let (permit_tx, permit_rx) = oneshot::channel::<()>();
+        let permit = permit_rx.shared();
+        let (tx, rx) = mpsc::unbounded();
+        let mut debounced =
+            pin!(rx.debounce_with(move |_| permit.clone().map(|_| ()), NonZeroUsize::MIN));
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        tx.unbounded_send(0).unwrap();
+        tx.unbounded_send(1).unwrap();
+        assert!(debounced.poll_next_unpin(&mut cx).is_pending());
+        assert!(debounced.poll_next_unpin(&mut cx).is_pending());
+
+        // Grant the permit.
+        permit_tx.send(()).unwrap();
+        assert_eq!(Poll::Ready(Some(1)), debounced.poll_next_unpin(&mut cx));
+
+        tx.unbounded_send(2).unwrap();
+        drop(tx);
+        assert_eq!(Poll::Ready(Some(2)), debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Ready(None), debounced.poll_next_unpin(&mut cx));
+    }
+}
@@ -0,0 +1,171 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::vec::Vec;
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Sleep, budget::ReadyBudget};
+
+use super::{BurstHandler, DebounceConfig, DebounceEdge, DebounceState, Received};
+
+/// Configuration of [`SessionWindows`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionWindowConfig {
+    /// Quiet gap
+    ///
+    /// Ends a session after no new items have arrived for this duration.
+    pub gap: Duration,
+
+    /// Maximum duration of a session
+    ///
+    /// Ends a session after this duration has passed since the first item
+    /// of the session has arrived, even if new items keep arriving.
+    ///
+    /// Sessions are unlimited if `None`.
+    pub max_duration: Option<Duration>,
+
+    /// Maximum number of items in a session
+    ///
+    /// Ends a session immediately when it contains this number of items.
+    ///
+    /// Sessions are unlimited if `None`.
+    pub max_items: Option<NonZeroUsize>,
+}
+
+/// Collects the items of a session.
+#[derive(Debug)]
+struct CollectSession {
+    max_items: Option<NonZeroUsize>,
+}
+
+impl<I> BurstHandler<I> for CollectSession {
+    type Pending = Vec<I>;
+
+    fn receive(&mut self, pending: &mut Option<Vec<I>>, item: I) -> Received<Vec<I>> {
+        let session = pending.get_or_insert_with(Vec::new);
+        session.push(item);
+        if self
+            .max_items
+            .is_some_and(|max_items| session.len() >= max_items.get())
+        {
+            // End the session immediately.
+            return Received::Flushed(pending.take().expect("some"));
+        }
+        Received::Debounced
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::session_windows()`](crate::StreamExt::session_windows).
+    #[derive(Debug)]
+    #[project = SessionWindowsProjected]
+    pub struct SessionWindows<St: Stream, S> {
+        // The gap is the delay and the maximum duration is the maximum
+        // wait time of a burst.
+        #[pin]
+        state: DebounceState<St, Vec<St::Item>, S, S>,
+
+        config: SessionWindowConfig,
+
+        collect: CollectSession,
+    }
+}
+
+impl<St, S> SessionWindows<St, S>
+where
+    St: Stream,
+    S: Sleep,
+{
+    pub(crate) const fn new(
+        stream: St,
+        config: SessionWindowConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            config,
+            collect: CollectSession {
+                max_items: config.max_items,
+            },
+        }
+    }
+}
+
+impl<St, S> Stream for SessionWindows<St, S>
+where
+    St: Stream,
+    S: Sleep,
+{
+    type Item = Vec<St::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let SessionWindowsProjected {
+            state,
+            config:
+                SessionWindowConfig {
+                    gap,
+                    max_duration,
+                    max_items: _,
+                },
+            collect,
+        } = self.project();
+        let config = DebounceConfig {
+            delay: *gap,
+            edge: DebounceEdge::Trailing,
+            max_wait: *max_duration,
+        };
+        state.poll_next_config(cx, &config, collect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    use super::SessionWindowConfig;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn session_windows() {
+        let config = SessionWindowConfig {
+            gap: TIME_TICK.saturating_mul(10),
+            max_duration: Some(TIME_TICK.saturating_mul(25)),
+            max_items: NonZeroUsize::new(4),
+        };
+
+        // ms:   0 | 2 | 4 | 30 | 31 | 32 | 33 | 34 | 60 | 68 | 76
+        // item: 0 | 1 | 2 |  3 |  4 |  5 |  6 |  7 |  8 |  9 | 10
+        let timed_items = [0, 2, 4, 30, 31, 32, 33, 34, 60, 68, 76];
+        let output = run_timed(timed_items.into_iter().zip(0..), 100, |rx| {
+            rx.session_windows::<VirtualSleep>(config, NonZeroUsize::MIN)
+        });
+
+        assert_eq!(
+            vec![
+                // Gap
+                (14, vec![0, 1, 2]),
+                // Maximum number of items
+                (33, vec![3, 4, 5, 6]),
+                // Gap
+                (44, vec![7]),
+                // Maximum duration
+                (85, vec![8, 9, 10]),
+            ],
+            output
+        );
+    }
+}
@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use embassy_time::{Instant, Timer};
+
+use crate::{SleepIntervalThrottler, ThrottleInterval, ThrottleIntervalConfig};
+
+mod debounce;
+
+mod throttle;
+
+impl crate::Sleep for Timer {
+    fn sleep(duration: Duration) -> Self {
+        // Saturate durations that exceed the range of the timer.
+        let duration =
+            embassy_time::Duration::try_from(duration).unwrap_or(embassy_time::Duration::MAX);
+        Timer::after(duration)
+    }
+}
+
+impl crate::Clock for Timer {
+    type Instant = Instant;
+
+    fn now() -> Self::Instant {
+        Instant::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.saturating_duration_since(earlier).into()
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        let duration = embassy_time::Duration::try_from(duration).ok()?;
+        instant.checked_add(duration)
+    }
+}
+
+impl ThrottleInterval for Timer {
+    type IntervalThrottler<T> = SleepIntervalThrottler<Self, T>;
+
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T> {
+        SleepIntervalThrottler::new(config)
+    }
+}
+
+/// Takes exclusive control of the global mock driver.
+///
+/// The time of the mock driver is reset. Tests that use the mock driver
+/// must not run concurrently.
+#[cfg(test)]
+fn lock_mock_driver() -> std::sync::MutexGuard<'static, ()> {
+    use std::sync::{Mutex, PoisonError};
+
+    static MOCK_DRIVER: Mutex<()> = Mutex::new(());
+
+    let guard = MOCK_DRIVER.lock().unwrap_or_else(PoisonError::into_inner);
+    embassy_time::MockDriver::get().reset();
+    guard
+}
+
+/// Advances the time of the global mock driver.
+#[cfg(test)]
+fn advance_mock_driver(duration: Duration) {
+    embassy_time::MockDriver::get().advance(duration.try_into().unwrap());
+}
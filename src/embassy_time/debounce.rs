@@ -0,0 +1,55 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use embassy_time::Timer;
+    use futures::{StreamExt as _, channel::mpsc, task::noop_waker_ref};
+
+    use crate::{
+        StreamExt,
+        embassy_time::{advance_mock_driver as advance, lock_mock_driver},
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn debounce() {
+        let _mock_driver = lock_mock_driver();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let (tx, rx) = mpsc::unbounded();
+        let mut debounced = rx.debounce::<Timer>(TIME_TICK.saturating_mul(10), NonZeroUsize::MIN);
+
+        tx.unbounded_send(0).unwrap();
+        tx.unbounded_send(1).unwrap();
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+
+        advance(TIME_TICK.saturating_mul(9));
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+
+        // Restarts the delay window.
+        tx.unbounded_send(2).unwrap();
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+
+        advance(TIME_TICK.saturating_mul(9));
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+
+        advance(TIME_TICK);
+        assert_eq!(Poll::Ready(Some(2)), debounced.poll_next_unpin(&mut cx));
+
+        tx.unbounded_send(3).unwrap();
+        drop(tx);
+        assert_eq!(Poll::Pending, debounced.poll_next_unpin(&mut cx));
+
+        advance(TIME_TICK.saturating_mul(10));
+        assert_eq!(Poll::Ready(Some(3)), debounced.poll_next_unpin(&mut cx));
+        assert_eq!(Poll::Ready(None), debounced.poll_next_unpin(&mut cx));
+    }
+}
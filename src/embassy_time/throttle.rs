@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        task::{Context, Poll},
+        time::Duration,
+    };
+
+    use embassy_time::Timer;
+    use futures::{StreamExt as _, channel::mpsc, task::noop_waker_ref};
+
+    use crate::{
+        IntervalEdge, StreamExt, ThrottleIntervalConfig,
+        embassy_time::{advance_mock_driver as advance, lock_mock_driver},
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn throttle_interval() {
+        let _mock_driver = lock_mock_driver();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let config = ThrottleIntervalConfig {
+            period: TIME_TICK.saturating_mul(10),
+            edge: IntervalEdge::Leading,
+        };
+        let (tx, rx) = mpsc::unbounded();
+        let mut throttled = rx.throttle_interval::<Timer>(config, NonZeroUsize::MIN);
+
+        // The first item is yielded immediately on the leading edge.
+        tx.unbounded_send(0).unwrap();
+        assert_eq!(Poll::Ready(Some(0)), throttled.poll_next_unpin(&mut cx));
+
+        tx.unbounded_send(1).unwrap();
+        tx.unbounded_send(2).unwrap();
+        assert_eq!(Poll::Pending, throttled.poll_next_unpin(&mut cx));
+
+        advance(TIME_TICK.saturating_mul(9));
+        assert_eq!(Poll::Pending, throttled.poll_next_unpin(&mut cx));
+
+        // Only the most recent item is yielded at the next tick.
+        advance(TIME_TICK);
+        assert_eq!(Poll::Ready(Some(2)), throttled.poll_next_unpin(&mut cx));
+
+        // Missed ticks are skipped.
+        tx.unbounded_send(3).unwrap();
+        advance(TIME_TICK.saturating_mul(25));
+        assert_eq!(Poll::Ready(Some(3)), throttled.poll_next_unpin(&mut cx));
+
+        tx.unbounded_send(4).unwrap();
+        assert_eq!(Poll::Pending, throttled.poll_next_unpin(&mut cx));
+        advance(TIME_TICK.saturating_mul(4));
+        assert_eq!(Poll::Pending, throttled.poll_next_unpin(&mut cx));
+        advance(TIME_TICK);
+        assert_eq!(Poll::Ready(Some(4)), throttled.poll_next_unpin(&mut cx));
+
+        drop(tx);
+        assert_eq!(Poll::Ready(None), throttled.poll_next_unpin(&mut cx));
+    }
+}
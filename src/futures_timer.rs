@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+
+use crate::{SleepIntervalThrottler, ThrottleInterval, ThrottleIntervalConfig};
+
+mod debounce;
+
+impl crate::Sleep for Delay {
+    fn sleep(duration: Duration) -> Self {
+        Delay::new(duration)
+    }
+
+    fn reset(self: Pin<&mut Self>, duration: Duration) {
+        Delay::reset(self.get_mut(), duration);
+    }
+}
+
+impl crate::Clock for Delay {
+    type Instant = Instant;
+
+    fn now() -> Self::Instant {
+        Instant::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.saturating_duration_since(earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant.checked_add(duration)
+    }
+}
+
+impl ThrottleInterval for Delay {
+    type IntervalThrottler<T> = SleepIntervalThrottler<Self, T>;
+
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T> {
+        SleepIntervalThrottler::new(config)
+    }
+}
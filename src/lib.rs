@@ -3,8 +3,16 @@
 
 //! Extensions of the [`Stream`] trait and utilities for
 //! transforming or shaping streams.
+//!
+//! The crate is `no_std` compatible if the default `std` feature is disabled.
+//! Operators that need to allocate memory are available with the `alloc` feature.
 
-use std::{num::NonZeroUsize, time::Duration};
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::{fmt, num::NonZeroUsize, pin::Pin, time::Duration};
 
 use futures_util::{Stream, StreamExt as _};
 
@@ -15,11 +23,48 @@ pub use self::distinct::{
     filter_distinct_until_changed_err_result, filter_distinct_until_changed_ok_result,
 };
 
+mod budget;
+
 mod debounce;
-pub use self::debounce::Debounced;
+#[cfg(feature = "alloc")]
+pub use self::debounce::{
+    AdaptiveDebounceConfig, AdaptiveDebounced, DebounceByKeyConfig, DebouncedByKey,
+    SessionWindowConfig, SessionWindows,
+};
+pub use self::debounce::{
+    DebounceConfig, DebounceEdge, Debounced, DebouncedBy, DebouncedDistinct, DebouncedFlushIf,
+    DebouncedFold, DebouncedOk, DebouncedWith, FlushPolicy,
+};
+#[cfg(feature = "std")]
+pub use self::debounce::{DebounceGroup, DebounceGroupDriver, GroupDebounced};
+
+mod stable;
+pub use self::stable::{StableFor, StableForEq};
 
 mod throttle;
-pub use self::throttle::{ThrottleIntervalConfig, Throttled, Throttler};
+pub use self::throttle::{
+    SleepIntervalThrottler, ThrottleInterval, ThrottleIntervalConfig, Throttled, Throttler,
+};
+
+#[cfg(feature = "async-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-io")))]
+pub mod async_io;
+
+#[cfg(feature = "embassy-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embassy-time")))]
+pub mod embassy_time;
+
+#[cfg(feature = "futures-timer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-timer")))]
+pub mod futures_timer;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod sync;
+
+#[cfg(any(test, feature = "testing"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
 
 #[cfg(feature = "tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
@@ -32,30 +77,383 @@ pub enum IntervalEdge {
     Trailing,
 }
 
-pub trait Sleep: Future<Output = ()> + Sized {
+/// Timer future of a runtime.
+///
+/// The output of the future is ignored.
+pub trait Sleep: Future + Sized {
     /// Suspends the task for the given duration.
     fn sleep(duration: Duration) -> Self;
+
+    /// Restarts the timer with the given duration.
+    ///
+    /// Streams restart their timers instead of creating new ones. The timer
+    /// might have elapsed before. The default implementation replaces the
+    /// timer with a new one. Implementations could override it to reuse
+    /// the resources of the timer.
+    fn reset(mut self: Pin<&mut Self>, duration: Duration) {
+        self.set(Self::sleep(duration));
+    }
 }
 
-/// Extension trait for [`Stream`].
-pub trait StreamExt: Stream {
-    /// Custom implementation of [`Sleep`].
-    type Sleep: Sleep;
+/// Clock of a [`Sleep`] implementation.
+///
+/// Provides the current time of the runtime for measuring
+/// elapsed time.
+pub trait Clock: Sleep {
+    /// Point in time.
+    type Instant: Copy + Ord + fmt::Debug;
 
-    /// Return type of [`throttle_interval()`](Self::throttle_interval).
-    type IntervalThrottler: Throttler<<Self as Stream>::Item>;
+    /// Returns the current time.
+    fn now() -> Self::Instant;
 
+    /// Returns the amount of time elapsed from `earlier` to `instant`.
+    ///
+    /// Returns zero if `earlier` is later than `instant`.
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration;
+
+    /// Returns `instant` + `duration` or `None` on overflow.
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant>;
+}
+
+/// Extension trait for [`Stream`].
+///
+/// Implemented for all streams. Operators that depend on timers are
+/// generic over the [`Sleep`] implementation of the desired runtime.
+pub trait StreamExt: Stream {
     /// Debounces an input stream.
     ///
     /// Delays items until no more new items have arrived during the delay window.
     ///
-    /// Filters out items that arrive in quick succession. Only the last item from
-    /// each sequence is emitted. All preceding items are discarded.
-    fn debounce(self, delay: Duration) -> Debounced<Self, Self::Sleep>
+    /// Filters out items that arrive in quick succession. Only the first and/or
+    /// the last item from each sequence is emitted, depending on the configured
+    /// [`DebounceEdge`]. All other items are discarded. A plain [`Duration`]
+    /// debounces on the trailing edge, i.e. only the last item is emitted.
+    ///
+    /// The `poll_next_max_ready_count` argument controls how many _Ready_ items
+    /// are polled subsequently in a row from the input stream during an invocation
+    /// of [`Stream::poll_next()`], like for [`throttle()`](Self::throttle). This
+    /// limit ensures that input streams which are always ready are not polled
+    /// forever. The delay restarts after each polled batch of items.
+    fn debounce<S>(
+        self,
+        config: impl Into<DebounceConfig>,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Debounced<Self, S>
+    where
+        Self: Sized,
+        S: Sleep,
+    {
+        Debounced::new(self, config.into(), poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream with a delay that depends on the pending item.
+    ///
+    /// The delay is determined by invoking `delay_fn` with the last item
+    /// that has been received. Items are emitted on the trailing edge.
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    fn debounce_by<S, F>(
+        self,
+        delay_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedBy<Self, S, F>
+    where
+        Self: Sized,
+        S: Sleep,
+        F: FnMut(&Self::Item) -> Duration,
+    {
+        DebouncedBy::new(self, delay_fn, poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream until a future completes.
+    ///
+    /// The future is created by invoking `delay_fn` with the last item that
+    /// has been received. The item is emitted when the future completes. Each
+    /// new item drops the pending future and replaces it with a new one.
+    /// The output of the future is ignored.
+    ///
+    /// The quiet period could be any completion condition, e.g. until a lock
+    /// has been released or a permit has been granted.
+    ///
+    /// See also: [`debounce_by()`](Self::debounce_by)
+    fn debounce_with<F, Fut>(
+        self,
+        delay_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedWith<Self, F, Fut>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> Fut,
+        Fut: Future,
+    {
+        DebouncedWith::new(self, delay_fn, poll_next_max_ready_count)
+    }
+
+    /// Emits a new value only after it has been stable for a duration.
+    ///
+    /// Suppresses glitches of state streams, e.g. contact bounce. A value
+    /// that differs from the previously emitted value is only emitted after
+    /// the input has stayed at this value for at least `duration`. Repeated
+    /// values do not restart the timer. Shorter glitches are ignored and the
+    /// previously emitted value stays in effect.
+    ///
+    /// Unlike [`debounce()`](Self::debounce) the input stream may keep
+    /// repeating the current value.
+    ///
+    /// See also: [`stable_for_by()`](Self::stable_for_by)
+    fn stable_for<S>(
+        self,
+        duration: Duration,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> StableForEq<Self, S>
     where
         Self: Sized,
+        Self::Item: Clone + PartialEq,
+        S: Sleep,
     {
-        Debounced::new(self, delay)
+        StableFor::new(self, duration, PartialEq::eq, poll_next_max_ready_count)
+    }
+
+    /// Emits a new value only after it has been stable for a duration.
+    ///
+    /// Values are considered equal if `eq_fn` returns `true`, e.g. if they
+    /// differ by less than a tolerance. The first item of a run of equal
+    /// items is emitted and all following items are compared with it.
+    ///
+    /// See also: [`stable_for()`](Self::stable_for)
+    fn stable_for_by<S, F>(
+        self,
+        duration: Duration,
+        eq_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> StableFor<Self, S, F>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        S: Sleep,
+        F: FnMut(&Self::Item, &Self::Item) -> bool,
+    {
+        StableFor::new(self, duration, eq_fn, poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream only while items arrive frequently.
+    ///
+    /// Items are passed through immediately while the arrival rate stays
+    /// below the configured threshold. Debouncing on the trailing edge
+    /// starts when the threshold is exceeded and stops again after the
+    /// burst has ended.
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn debounce_adaptive<S>(
+        self,
+        config: AdaptiveDebounceConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> AdaptiveDebounced<Self, S>
+    where
+        Self: Sized,
+        S: Clock,
+    {
+        AdaptiveDebounced::new(self, config, poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream and filters out duplicates.
+    ///
+    /// Items that are equal to the pending item neither replace it nor
+    /// restart the delay window. Items that are equal to the previously
+    /// emitted item are never emitted again in a row.
+    ///
+    /// Unlike [`distinct_until_changed()`] followed by [`debounce()`](Self::debounce)
+    /// repeated values do not postpone a changed value.
+    fn debounce_distinct<S>(
+        self,
+        config: impl Into<DebounceConfig>,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedDistinct<Self, S>
+    where
+        Self: Sized,
+        Self::Item: Clone + PartialEq,
+        S: Sleep,
+    {
+        DebouncedDistinct::new(self, config.into(), poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream except for urgent items.
+    ///
+    /// Items for which `flush_if` returns `true` do not wait for the delay
+    /// window and are emitted immediately. The pending item of the current
+    /// burst is handled according to the [`FlushPolicy`]. An urgent item
+    /// ends the current burst. All other items are debounced like with
+    /// [`debounce()`](Self::debounce).
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    fn debounce_flush_if<S, F>(
+        self,
+        config: impl Into<DebounceConfig>,
+        flush_if: F,
+        policy: FlushPolicy,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedFlushIf<Self, S, F>
+    where
+        Self: Sized,
+        S: Sleep,
+        F: FnMut(&Self::Item) -> bool,
+    {
+        DebouncedFlushIf::new(
+            self,
+            config.into(),
+            flush_if,
+            policy,
+            poll_next_max_ready_count,
+        )
+    }
+
+    /// Debounces the `Ok` items of a result stream.
+    ///
+    /// `Err` items do not wait for the delay window and are emitted
+    /// immediately. The pending `Ok` item is handled according to the
+    /// [`FlushPolicy`].
+    ///
+    /// See also: [`debounce_flush_if()`](Self::debounce_flush_if)
+    fn debounce_ok<S, T, E>(
+        self,
+        config: impl Into<DebounceConfig>,
+        policy: FlushPolicy,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedOk<Self, S>
+    where
+        Self: Stream<Item = Result<T, E>> + Sized,
+        S: Sleep,
+    {
+        DebouncedFlushIf::new(
+            self,
+            config.into(),
+            Result::is_err,
+            policy,
+            poll_next_max_ready_count,
+        )
+    }
+
+    /// Debounces an input stream by folding all items of a burst.
+    ///
+    /// Each item is folded into the pending accumulator with `fold_fn`.
+    /// The accumulator is emitted when no more new items have arrived during
+    /// the delay window. Each burst starts with a clone of `init`.
+    ///
+    /// On the leading edge the first item of a burst is folded separately
+    /// and emitted immediately. The remaining items of the burst are folded
+    /// into a new accumulator that is only emitted on the trailing edge.
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    fn debounce_fold<S, A, F>(
+        self,
+        config: impl Into<DebounceConfig>,
+        init: A,
+        fold_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedFold<Self, S, A, F>
+    where
+        Self: Sized,
+        S: Sleep,
+        A: Clone,
+        F: FnMut(A, Self::Item) -> A,
+    {
+        DebouncedFold::new(
+            self,
+            config.into(),
+            init,
+            fold_fn,
+            poll_next_max_ready_count,
+        )
+    }
+
+    /// Debounces the items of each key independently.
+    ///
+    /// Every key that is extracted from the items with `key_fn` has its
+    /// own pending item and quiet period. Items of different keys never
+    /// replace each other.
+    ///
+    /// Keys are only tracked while they have a pending item. The number
+    /// of tracked keys is limited by [`DebounceByKeyConfig::max_keys`].
+    /// A single timer is used for all keys.
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn debounce_by_key<S, K, F>(
+        self,
+        config: DebounceByKeyConfig,
+        key_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> DebouncedByKey<Self, S, K, F>
+    where
+        Self: Sized,
+        S: Clock,
+        K: Ord + Clone,
+        F: FnMut(&Self::Item) -> K,
+    {
+        DebouncedByKey::new(self, config, key_fn, poll_next_max_ready_count)
+    }
+
+    /// Collects the items of bursts into session windows.
+    ///
+    /// Keeps all items that arrive during a burst and emits them as a batch
+    /// when no new item has arrived for the configured gap. Sessions are
+    /// also ended after reaching a maximum duration or a maximum number of
+    /// items to limit the memory consumption.
+    ///
+    /// See also: [`debounce_fold()`](Self::debounce_fold)
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn session_windows<S>(
+        self,
+        config: SessionWindowConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> SessionWindows<Self, S>
+    where
+        Self: Sized,
+        S: Sleep,
+    {
+        SessionWindows::new(self, config, poll_next_max_ready_count)
+    }
+
+    /// Debounces an input stream that is polled cooperatively.
+    ///
+    /// Polls the input stream only while the cooperative scheduling budget
+    /// of the current tokio task allows. Input streams that are always ready
+    /// will then yield to other tasks regularly.
+    ///
+    /// The budget is unlimited outside of a tokio runtime or within
+    /// `tokio::task::coop::unconstrained()`.
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn debounce_coop<S>(self, config: impl Into<DebounceConfig>) -> Debounced<Self, S>
+    where
+        Self: Sized,
+        S: Sleep,
+    {
+        Debounced::with_coop_budget(self, config.into())
+    }
+
+    /// Debounces an input stream with the shared timer of a [`DebounceGroup`].
+    ///
+    /// See also: [`debounce()`](Self::debounce)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn debounce_grouped<S>(
+        self,
+        group: &DebounceGroup<S>,
+        delay: Duration,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> GroupDebounced<Self, S>
+    where
+        Self: Sized,
+        S: Clock,
+    {
+        group.debounce(self, delay, poll_next_max_ready_count)
     }
 
     /// Throttles an input stream.
@@ -85,14 +483,66 @@ pub trait StreamExt: Stream {
 
     /// Throttles an input stream by using a fixed interval.
     ///
+    /// The interval throttler is provided by the [`Sleep`] implementation `S`.
+    ///
     /// See also: [`throttle()`](Self::throttle)
-    fn throttle_interval(
+    fn throttle_interval<S>(
+        self,
+        config: ThrottleIntervalConfig,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Throttled<Self, S::IntervalThrottler<Self::Item>>
+    where
+        Self: Sized,
+        S: ThrottleInterval,
+    {
+        self.throttle(S::interval_throttler(config), poll_next_max_ready_count)
+    }
+
+    /// Throttles an input stream that is polled cooperatively.
+    ///
+    /// Replaces the `poll_next_max_ready_count` argument of [`throttle()`](Self::throttle)
+    /// by the cooperative scheduling budget of the current tokio task. The
+    /// input stream is polled while the budget allows.
+    ///
+    /// The budget is unlimited outside of a tokio runtime or within
+    /// `tokio::task::coop::unconstrained()`.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn throttle_coop<T>(self, throttler: T) -> Throttled<Self, T>
+    where
+        Self: Sized,
+        T: Throttler<Self::Item>,
+    {
+        Throttled::with_coop_budget(self, throttler)
+    }
+
+    /// Throttles an input stream that is polled cooperatively by using
+    /// a fixed interval.
+    ///
+    /// See also: [`throttle_coop()`](Self::throttle_coop), [`throttle_interval()`](Self::throttle_interval)
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    fn throttle_interval_coop<S>(
         self,
         config: ThrottleIntervalConfig,
-        poll_next_max_ready_count: std::num::NonZeroUsize,
-    ) -> Throttled<Self, Self::IntervalThrottler>
+    ) -> Throttled<Self, S::IntervalThrottler<Self::Item>>
     where
-        Self: Sized;
+        Self: Sized,
+        S: ThrottleInterval,
+    {
+        self.throttle_coop(S::interval_throttler(config))
+    }
+}
+
+impl<St: Stream> StreamExt for St {}
+
+/// Restarts the timer or creates a new one.
+fn restart_sleep<S: Sleep>(mut sleep: Pin<&mut Option<S>>, duration: Duration) {
+    if let Some(sleep) = sleep.as_mut().as_pin_mut() {
+        sleep.reset(duration);
+    } else {
+        sleep.set(Some(S::sleep(duration)));
+    }
 }
 
 fn filter_stateful<S, T, F, G>(
@@ -119,6 +569,6 @@ where
     F: FnMut(&mut T, &S::Item) -> bool,
 {
     filter_stateful(stream, initial_state, move |state, next_item| {
-        std::future::ready(filter_update_state_fn(state, next_item))
+        core::future::ready(filter_update_state_fn(state, next_item))
     })
 }
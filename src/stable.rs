@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    num::NonZeroUsize,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{
+    DebounceConfig, Sleep,
+    budget::ReadyBudget,
+    debounce::{BurstHandler, DebounceEdge, DebounceState, Received},
+};
+
+/// Keeps the first item of a run of equal items as the pending candidate.
+#[derive(Debug)]
+struct StableCandidate<T, F> {
+    eq_fn: F,
+
+    // The most recently emitted item.
+    stable: Option<T>,
+}
+
+impl<T, F> BurstHandler<T> for StableCandidate<T, F>
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    type Pending = T;
+
+    fn receive(&mut self, candidate: &mut Option<T>, item: T) -> Received<T> {
+        let Self { eq_fn, stable } = self;
+        if candidate
+            .as_ref()
+            .is_some_and(|candidate| eq_fn(candidate, &item))
+        {
+            // Repeated values do not restart the timer.
+            return Received::Ignored;
+        }
+        if stable.as_ref().is_some_and(|stable| eq_fn(stable, &item)) {
+            // The glitch has ended and the stable value stays in effect.
+            // The running timer expires without emitting anything.
+            *candidate = None;
+            return Received::Ignored;
+        }
+        *candidate = Some(item);
+        Received::Debounced
+    }
+}
+
+pin_project! {
+    /// Result of [`StreamExt::stable_for_by()`](crate::StreamExt::stable_for_by).
+    #[derive(Debug)]
+    #[project = StableForProjected]
+    pub struct StableFor<St: Stream, S, F> {
+        // The pending item is the candidate for the next stable value.
+        #[pin]
+        state: DebounceState<St, St::Item, S, S>,
+
+        duration: Duration,
+
+        candidate: StableCandidate<St::Item, F>,
+    }
+}
+
+/// Result of [`StreamExt::stable_for()`](crate::StreamExt::stable_for).
+pub type StableForEq<St, S> =
+    StableFor<St, S, fn(&<St as Stream>::Item, &<St as Stream>::Item) -> bool>;
+
+impl<St, S, F> StableFor<St, S, F>
+where
+    St: Stream,
+    St::Item: Clone,
+    S: Sleep,
+    F: FnMut(&St::Item, &St::Item) -> bool,
+{
+    pub(crate) const fn new(
+        stream: St,
+        duration: Duration,
+        eq_fn: F,
+        poll_next_max_ready_count: NonZeroUsize,
+    ) -> Self {
+        Self {
+            state: DebounceState::new(stream, ReadyBudget::MaxCount(poll_next_max_ready_count)),
+            duration,
+            candidate: StableCandidate {
+                eq_fn,
+                stable: None,
+            },
+        }
+    }
+}
+
+impl<St, S, F> Stream for StableFor<St, S, F>
+where
+    St: Stream,
+    St::Item: Clone,
+    S: Sleep,
+    F: FnMut(&St::Item, &St::Item) -> bool,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let StableForProjected {
+            state,
+            duration,
+            candidate,
+        } = self.project();
+        let config = DebounceConfig {
+            delay: *duration,
+            edge: DebounceEdge::Trailing,
+            max_wait: None,
+        };
+        let next_item = ready!(state.poll_next_config(cx, &config, candidate));
+        if let Some(next_item) = &next_item {
+            // The new value has been stable for long enough.
+            candidate.stable = Some(next_item.clone());
+        }
+        Poll::Ready(next_item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use crate::{
+        StreamExt as _,
+        testing::{VirtualSleep, run_timed},
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn stable_for_should_ignore_glitches() {
+        // The input repeats its current value every 2 ms with glitches
+        // to 1 at 20 ms and 2 at 24 ms.
+        //
+        // ms:    0..20 | 20..24 | 24..26 | 26..40 | 40..60
+        // value:     0 |      1 |      2 |      0 |      1
+        let timed_items = (0..60).step_by(2).map(|millis| {
+            let value = match millis {
+                20..24 | 40.. => 1,
+                24..26 => 2,
+                _ => 0,
+            };
+            (millis, value)
+        });
+        let output = run_timed(timed_items, 60, |rx| {
+            rx.stable_for::<VirtualSleep>(TIME_TICK.saturating_mul(10), NonZeroUsize::MIN)
+        });
+
+        assert_eq!(vec![(10, 0), (50, 1)], output);
+    }
+
+    #[test]
+    fn stable_for_by_should_compare_with_a_tolerance() {
+        // ms:    0 | 4 | 8 | 12 | 16 | 20
+        // value: 0 | 1 | 0 |  5 |  6 |  5
+        let timed_items = [(0, 0), (4, 1), (8, 0), (12, 5), (16, 6), (20, 5)];
+        let output = run_timed(timed_items, 40, |rx| {
+            rx.stable_for_by::<VirtualSleep, _>(
+                TIME_TICK.saturating_mul(10),
+                |lhs: &i32, rhs: &i32| lhs.abs_diff(*rhs) <= 1,
+                NonZeroUsize::MIN,
+            )
+        });
+
+        assert_eq!(vec![(10, 0), (22, 5)], output);
+    }
+}
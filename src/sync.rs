@@ -0,0 +1,620 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Blocking counterparts for iterators and channels.
+//!
+//! The adapters block the current thread and behave like their asynchronous
+//! counterparts [`Debounced`](crate::Debounced) and [`Throttled`](crate::Throttled)
+//! with an interval throttler.
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    IntervalEdge, ThrottleIntervalConfig,
+    distinct::filter_distinct_until_changed,
+    throttle::{InstantArithmetic, next_deadline},
+};
+
+/// Receiver of a channel that blocks the current thread.
+///
+/// Implemented for [`std::sync::mpsc::Receiver`] and, with the
+/// `crossbeam-channel` feature, for `crossbeam_channel::Receiver`.
+/// Receivers of other channels could be adapted by implementing this
+/// trait for a newtype.
+pub trait BlockingRecv {
+    /// Received item.
+    type Item;
+
+    /// Waits for the next item.
+    ///
+    /// Returns `None` if the channel has been disconnected.
+    fn recv(&mut self) -> Option<Self::Item>;
+
+    /// Waits for the next item until the timeout has elapsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecvTimeoutError::Timeout`] if no item has been received
+    /// in time or [`RecvTimeoutError::Disconnected`] if the channel has been
+    /// disconnected.
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Self::Item, RecvTimeoutError>;
+}
+
+impl<T> BlockingRecv for mpsc::Receiver<T> {
+    type Item = T;
+
+    fn recv(&mut self) -> Option<Self::Item> {
+        mpsc::Receiver::recv(self).ok()
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Self::Item, RecvTimeoutError> {
+        mpsc::Receiver::recv_timeout(self, timeout)
+    }
+}
+
+#[cfg(feature = "crossbeam-channel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "crossbeam-channel")))]
+impl<T> BlockingRecv for crossbeam_channel::Receiver<T> {
+    type Item = T;
+
+    fn recv(&mut self) -> Option<Self::Item> {
+        crossbeam_channel::Receiver::recv(self).ok()
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<Self::Item, RecvTimeoutError> {
+        crossbeam_channel::Receiver::recv_timeout(self, timeout).map_err(|err| match err {
+            crossbeam_channel::RecvTimeoutError::Timeout => RecvTimeoutError::Timeout,
+            crossbeam_channel::RecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected,
+        })
+    }
+}
+
+/// Time source of the blocking adapters.
+///
+/// The counterpart of [`Clock`](crate::Clock) that blocks the current
+/// thread instead of creating a timer future. Implemented by [`SystemClock`].
+pub trait BlockingClock {
+    /// Point in time.
+    type Instant: Copy + Ord + fmt::Debug;
+
+    /// Returns the current time.
+    fn now() -> Self::Instant;
+
+    /// Returns the amount of time elapsed from `earlier` to `instant`.
+    ///
+    /// Returns zero if `earlier` is later than `instant`.
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration;
+
+    /// Returns `instant` + `duration` or `None` on overflow.
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant>;
+
+    /// Blocks the current thread until the deadline has been reached.
+    fn sleep_until(deadline: Self::Instant);
+}
+
+/// [`BlockingClock`] of the operating system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl BlockingClock for SystemClock {
+    type Instant = Instant;
+
+    fn now() -> Self::Instant {
+        Instant::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.saturating_duration_since(earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant.checked_add(duration)
+    }
+
+    fn sleep_until(deadline: Self::Instant) {
+        let now = Instant::now();
+        if deadline > now {
+            thread::sleep(deadline - now);
+        }
+    }
+}
+
+/// Waits for the next item until the deadline is reached.
+fn recv_until<C: BlockingClock, R: BlockingRecv>(
+    rx: &mut R,
+    deadline: C::Instant,
+) -> Result<R::Item, RecvTimeoutError> {
+    let now = C::now();
+    if now >= deadline {
+        return Err(RecvTimeoutError::Timeout);
+    }
+    rx.recv_timeout(C::saturating_duration_since(deadline, now))
+}
+
+/// Filters out subsequent/adjacent iterator items.
+///
+/// See also: [`distinct_until_changed()`](crate::distinct_until_changed)
+pub fn distinct_until_changed<I>(iter: I) -> impl Iterator<Item = I::Item>
+where
+    I: IntoIterator,
+    I::Item: Clone + PartialEq,
+{
+    let mut last_item = None;
+    iter.into_iter()
+        .filter(move |next_item| filter_distinct_until_changed(&mut last_item, next_item))
+}
+
+/// Debounces the items of a blocking receiver.
+///
+/// See also: [`StreamExt::debounce()`](crate::StreamExt::debounce)
+pub const fn debounce_recv<R: BlockingRecv>(rx: R, delay: Duration) -> DebounceRecv<R> {
+    debounce_recv_with_clock(rx, delay)
+}
+
+/// Debounces the items of a blocking receiver with a custom clock.
+///
+/// See also: [`debounce_recv()`]
+pub const fn debounce_recv_with_clock<R: BlockingRecv, C: BlockingClock>(
+    rx: R,
+    delay: Duration,
+) -> DebounceRecv<R, C> {
+    DebounceRecv {
+        rx,
+        delay,
+        finished: false,
+        _clock: PhantomData,
+    }
+}
+
+/// Result of [`debounce_recv()`].
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct DebounceRecv<R, C = SystemClock> {
+    rx: R,
+    delay: Duration,
+    finished: bool,
+    _clock: PhantomData<fn() -> C>,
+}
+
+impl<R: BlockingRecv, C: BlockingClock> Iterator for DebounceRecv<R, C> {
+    type Item = R::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let Some(mut pending) = self.rx.recv() else {
+            self.finished = true;
+            return None;
+        };
+        let mut deadline = C::checked_add(C::now(), self.delay);
+        loop {
+            let Some(until) = deadline else {
+                // Never ready.
+                while self.rx.recv().is_some() {}
+                self.finished = true;
+                return None;
+            };
+            match recv_until::<C, _>(&mut self.rx, until) {
+                Ok(next_item) => {
+                    // Replace the pending item and restart the delay.
+                    pending = next_item;
+                    deadline = C::checked_add(C::now(), self.delay);
+                }
+                Err(RecvTimeoutError::Timeout) => return Some(pending),
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.finished = true;
+                    C::sleep_until(until);
+                    return Some(pending);
+                }
+            }
+        }
+    }
+}
+
+/// Throttles the items of a blocking receiver by using a fixed interval.
+///
+/// Missed ticks are skipped.
+///
+/// See also: [`StreamExt::throttle_interval()`](crate::StreamExt::throttle_interval)
+pub const fn throttle_recv<R: BlockingRecv>(
+    rx: R,
+    config: ThrottleIntervalConfig,
+) -> ThrottleRecv<R> {
+    throttle_recv_with_clock(rx, config)
+}
+
+/// Throttles the items of a blocking receiver with a custom clock.
+///
+/// See also: [`throttle_recv()`]
+pub const fn throttle_recv_with_clock<R: BlockingRecv, C: BlockingClock>(
+    rx: R,
+    config: ThrottleIntervalConfig,
+) -> ThrottleRecv<R, C> {
+    ThrottleRecv {
+        rx,
+        config,
+        next_tick: Tick::Idle,
+        pending: None,
+        finished: false,
+    }
+}
+
+/// The next tick of [`ThrottleRecv`].
+#[derive(Debug, Clone, Copy)]
+enum Tick<I> {
+    /// Waiting for the next item.
+    Idle,
+    At(I),
+    /// The deadline is not representable.
+    Never,
+}
+
+/// Adapts a [`BlockingClock`] for calculating the ticks of the interval.
+#[derive(Debug)]
+struct BlockingInstants<C>(PhantomData<C>);
+
+impl<C: BlockingClock> InstantArithmetic for BlockingInstants<C> {
+    type Instant = C::Instant;
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        C::saturating_duration_since(instant, earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        C::checked_add(instant, duration)
+    }
+}
+
+/// Result of [`throttle_recv()`].
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct ThrottleRecv<R: BlockingRecv, C: BlockingClock = SystemClock> {
+    rx: R,
+    config: ThrottleIntervalConfig,
+    next_tick: Tick<C::Instant>,
+    pending: Option<R::Item>,
+    finished: bool,
+}
+
+impl<R: BlockingRecv, C: BlockingClock> Iterator for ThrottleRecv<R, C> {
+    type Item = R::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ThrottleIntervalConfig { period, edge } = self.config;
+        loop {
+            let tick = match self.next_tick {
+                Tick::Idle => {
+                    // Idle until the next item arrives.
+                    if self.finished {
+                        return None;
+                    }
+                    let Some(next_item) = self.rx.recv() else {
+                        self.finished = true;
+                        return None;
+                    };
+                    self.pending = Some(next_item);
+                    let now = C::now();
+                    self.next_tick = match edge {
+                        IntervalEdge::Leading => Some(now),
+                        IntervalEdge::Trailing => C::checked_add(now, period),
+                    }
+                    .map_or(Tick::Never, Tick::At);
+                    continue;
+                }
+                Tick::At(tick) => tick,
+                Tick::Never => {
+                    // Never ready.
+                    while !self.finished && self.rx.recv().is_some() {}
+                    self.finished = true;
+                    return None;
+                }
+            };
+            if !self.finished {
+                match recv_until::<C, _>(&mut self.rx, tick) {
+                    Ok(next_item) => {
+                        self.pending = Some(next_item);
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        self.finished = true;
+                        if self.pending.is_some() {
+                            C::sleep_until(tick);
+                        }
+                    }
+                }
+            }
+            let Some(next_item) = self.pending.take() else {
+                // Stop the timer until the next item arrives.
+                self.next_tick = Tick::Idle;
+                continue;
+            };
+            self.next_tick = if self.finished || period.is_zero() {
+                Tick::At(tick)
+            } else {
+                next_deadline::<BlockingInstants<C>>(tick, period, C::now())
+                    .map_or(Tick::Never, Tick::At)
+            };
+            return Some(next_item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::mpsc, time::Duration};
+
+    use crate::{
+        IntervalEdge, ThrottleIntervalConfig,
+        testing::{VirtualClock, VirtualSleep},
+    };
+
+    use super::{
+        BlockingRecv, RecvTimeoutError, debounce_recv_with_clock, distinct_until_changed,
+        throttle_recv, throttle_recv_with_clock,
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    /// Receives the items at the given times (in ticks).
+    ///
+    /// Blocking advances the virtual clock. Items that arrive at the same
+    /// time as a timer expires are received first. Disconnects after the
+    /// last item.
+    #[derive(Debug)]
+    struct VirtualRecv {
+        clock: VirtualClock,
+        items: VecDeque<(u32, usize)>,
+    }
+
+    impl VirtualRecv {
+        fn new(clock: &VirtualClock, items: impl IntoIterator<Item = (u32, usize)>) -> Self {
+            Self {
+                clock: clock.clone(),
+                items: items.into_iter().collect(),
+            }
+        }
+
+        fn advance_until(&self, elapsed: Duration) {
+            self.clock
+                .advance(elapsed.saturating_sub(self.clock.elapsed()));
+        }
+    }
+
+    impl BlockingRecv for VirtualRecv {
+        type Item = usize;
+
+        fn recv(&mut self) -> Option<Self::Item> {
+            let (ticks, item) = self.items.pop_front()?;
+            self.advance_until(TIME_TICK.saturating_mul(ticks));
+            Some(item)
+        }
+
+        fn recv_timeout(&mut self, timeout: Duration) -> Result<Self::Item, RecvTimeoutError> {
+            let &(ticks, _) = self.items.front().ok_or(RecvTimeoutError::Disconnected)?;
+            let deadline = self.clock.elapsed().saturating_add(timeout);
+            if TIME_TICK.saturating_mul(ticks) > deadline {
+                self.advance_until(deadline);
+                return Err(RecvTimeoutError::Timeout);
+            }
+            Ok(self.recv().expect("item"))
+        }
+    }
+
+    /// Collects the items with their arrival times (in ticks).
+    fn collect_ticks(
+        clock: &VirtualClock,
+        items: impl Iterator<Item = usize>,
+        num_items: usize,
+    ) -> Vec<(u128, usize)> {
+        items
+            .take(num_items)
+            .map(|item| (clock.elapsed().as_millis() / TIME_TICK.as_millis(), item))
+            .collect()
+    }
+
+    fn periodic(period: u32, num_items: usize) -> impl Iterator<Item = (u32, usize)> {
+        (0..num_items).map(move |i| (period * u32::try_from(i).unwrap(), i))
+    }
+
+    fn alternating(first_delay: u32, second_delay: u32) -> impl Iterator<Item = (u32, usize)> {
+        (0..40).map(move |i| {
+            let n = u32::try_from(i).unwrap();
+            (first_delay * n.div_ceil(2) + second_delay * (n / 2), i)
+        })
+    }
+
+    #[test]
+    fn distinct_until_changed_iter() {
+        assert_eq!(
+            vec![1, 2, 1, 3],
+            distinct_until_changed([1, 1, 2, 2, 2, 1, 3, 3]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn debounce() {
+        let clock = VirtualClock::new();
+        let expected = [(10, 0), (27, 1), (44, 2), (61, 3), (78, 4)];
+        let rx = VirtualRecv::new(&clock, periodic(17, expected.len()));
+        assert_eq!(
+            expected.as_slice(),
+            collect_ticks(
+                &clock,
+                debounce_recv_with_clock::<_, VirtualSleep>(rx, TIME_TICK.saturating_mul(10)),
+                expected.len()
+            )
+        );
+    }
+
+    #[test]
+    fn debounce_should_yield_the_last_item_after_disconnect() {
+        let clock = VirtualClock::new();
+        let rx = VirtualRecv::new(&clock, [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(
+            vec![(12, 2)],
+            collect_ticks(
+                &clock,
+                debounce_recv_with_clock::<_, VirtualSleep>(rx, TIME_TICK.saturating_mul(10)),
+                10
+            )
+        );
+    }
+
+    #[test]
+    fn debounce_should_finish_with_an_unrepresentable_delay() {
+        let clock = VirtualClock::new();
+        let rx = VirtualRecv::new(&clock, [(1, 0), (2, 1)]);
+        assert_eq!(
+            None,
+            debounce_recv_with_clock::<_, VirtualSleep>(rx, Duration::MAX).next()
+        );
+    }
+
+    #[test]
+    fn throttle_should_pass_through_with_an_empty_period() {
+        for edge in [IntervalEdge::Leading, IntervalEdge::Trailing] {
+            let config = ThrottleIntervalConfig {
+                period: Duration::ZERO,
+                edge,
+            };
+            let (tx, rx) = mpsc::channel();
+            for item in 0..10 {
+                tx.send(item).unwrap();
+            }
+            drop(tx);
+            assert_eq!(
+                (0..10).collect::<Vec<_>>(),
+                throttle_recv(rx, config).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn leading_edge_sequence() {
+        let clock = VirtualClock::new();
+        let config = ThrottleIntervalConfig {
+            period: TIME_TICK.saturating_mul(19),
+            edge: IntervalEdge::Leading,
+        };
+        let expected = [
+            (0, 0),
+            (20, 1),
+            (39, 2),
+            (58, 3),
+            (77, 4),
+            (96, 6),
+            (115, 7),
+            (134, 8),
+            (153, 10),
+            (172, 11),
+            (191, 12),
+            (210, 14),
+        ];
+        let rx = VirtualRecv::new(&clock, alternating(20, 10));
+        assert_eq!(
+            expected.as_slice(),
+            collect_ticks(
+                &clock,
+                throttle_recv_with_clock::<_, VirtualSleep>(rx, config),
+                expected.len()
+            )
+        );
+    }
+
+    #[test]
+    fn trailing_edge_sequence() {
+        let clock = VirtualClock::new();
+        let config = ThrottleIntervalConfig {
+            period: TIME_TICK.saturating_mul(19),
+            edge: IntervalEdge::Trailing,
+        };
+        let expected = [
+            (19, 1),
+            (38, 2),
+            (57, 3),
+            (76, 5),
+            (95, 6),
+            (114, 7),
+            (133, 9),
+            (152, 10),
+            (171, 11),
+            (190, 13),
+            (229, 15),
+        ];
+        let rx = VirtualRecv::new(&clock, alternating(10, 20));
+        assert_eq!(
+            expected.as_slice(),
+            collect_ticks(
+                &clock,
+                throttle_recv_with_clock::<_, VirtualSleep>(rx, config),
+                expected.len()
+            )
+        );
+    }
+
+    #[cfg(feature = "crossbeam-channel")]
+    #[test]
+    fn crossbeam_channel() {
+        use super::debounce_recv;
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        for item in 0..10 {
+            tx.send(item).unwrap();
+        }
+        drop(tx);
+        assert_eq!(vec![9], debounce_recv(rx, TIME_TICK).collect::<Vec<_>>());
+
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let config = ThrottleIntervalConfig {
+            period: Duration::ZERO,
+            edge: IntervalEdge::Leading,
+        };
+        let sender = std::thread::spawn(move || {
+            for item in 0..10 {
+                tx.send(item).unwrap();
+            }
+        });
+        assert_eq!(
+            (0..10).collect::<Vec<_>>(),
+            throttle_recv(rx, config).collect::<Vec<_>>()
+        );
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn throttle_should_finish_after_disconnect() {
+        for period in [Duration::ZERO, TIME_TICK] {
+            for edge in [IntervalEdge::Leading, IntervalEdge::Trailing] {
+                let clock = VirtualClock::new();
+                let config = ThrottleIntervalConfig { period, edge };
+                let rx = VirtualRecv::new(&clock, [(0, 0)]);
+                assert_eq!(
+                    vec![0],
+                    throttle_recv_with_clock::<_, VirtualSleep>(rx, config).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn throttle_should_finish_with_an_unrepresentable_period() {
+        let clock = VirtualClock::new();
+        let config = ThrottleIntervalConfig {
+            period: Duration::MAX,
+            edge: IntervalEdge::Trailing,
+        };
+        let rx = VirtualRecv::new(&clock, [(1, 0), (2, 1)]);
+        assert_eq!(
+            None,
+            throttle_recv_with_clock::<_, VirtualSleep>(rx, config).next()
+        );
+    }
+}
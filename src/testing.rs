@@ -0,0 +1,493 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! Utilities for testing time-dependent streams deterministically.
+
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{Clock, Sleep, SleepIntervalThrottler, ThrottleInterval, ThrottleIntervalConfig};
+
+#[derive(Debug, Default)]
+struct ClockState {
+    elapsed: Duration,
+    next_timer_id: u64,
+    timers: BTreeMap<(Duration, u64), Waker>,
+}
+
+thread_local! {
+    static CURRENT_CLOCK: RefCell<Option<Rc<RefCell<ClockState>>>> = const { RefCell::new(None) };
+}
+
+fn current_clock_state() -> Rc<RefCell<ClockState>> {
+    CURRENT_CLOCK
+        .with(|current| current.borrow().clone())
+        .expect("no virtual clock for the current thread")
+}
+
+/// Point in time of a [`VirtualClock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VirtualInstant {
+    elapsed: Duration,
+}
+
+impl VirtualInstant {
+    /// The virtual time that has elapsed since the clock has been created.
+    #[must_use]
+    pub const fn elapsed_since_start(self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Virtual time that only advances on demand.
+///
+/// Creating a clock installs it as the current clock of the thread.
+/// All [`VirtualSleep`] timers that are created afterwards on the same
+/// thread are driven by this clock and only fire when the clock is
+/// advanced explicitly.
+///
+/// Works with any executor, e.g. [`LocalPool`](https://docs.rs/futures/latest/futures/executor/struct.LocalPool.html).
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    state: Rc<RefCell<ClockState>>,
+}
+
+impl VirtualClock {
+    /// Creates a new clock and installs it for the current thread.
+    ///
+    /// Replaces the previously installed clock of the current thread.
+    #[must_use]
+    pub fn new() -> Self {
+        let state = Rc::new(RefCell::new(ClockState::default()));
+        CURRENT_CLOCK.with(|current| *current.borrow_mut() = Some(Rc::clone(&state)));
+        Self { state }
+    }
+
+    /// The virtual time that has elapsed since the clock has been created.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.state.borrow().elapsed
+    }
+
+    /// The current virtual time.
+    #[must_use]
+    pub fn now(&self) -> VirtualInstant {
+        VirtualInstant {
+            elapsed: self.elapsed(),
+        }
+    }
+
+    /// The number of timers that are currently waiting to fire.
+    #[cfg(all(test, feature = "std"))]
+    pub(crate) fn pending_timer_count(&self) -> usize {
+        self.state.borrow().timers.len()
+    }
+
+    /// Advances the virtual time.
+    ///
+    /// The time advances in a single step. Wakes up all tasks with
+    /// expired timers afterwards.
+    pub fn advance(&self, duration: Duration) {
+        let expired_wakers = {
+            let mut state = self.state.borrow_mut();
+            let elapsed = state.elapsed.saturating_add(duration);
+            state.elapsed = elapsed;
+            let pending_timers = state.timers.split_off(&(elapsed, u64::MAX));
+            std::mem::replace(&mut state.timers, pending_timers)
+        };
+        // Wake up tasks after releasing the borrowed state.
+        for waker in expired_wakers.into_values() {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`Sleep`] implementation that is driven by a [`VirtualClock`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct VirtualSleep {
+    state: Rc<RefCell<ClockState>>,
+    deadline: Duration,
+    timer_id: u64,
+}
+
+impl Sleep for VirtualSleep {
+    /// Creates a timer for the current clock of the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`VirtualClock`] has been created on the current thread.
+    fn sleep(duration: Duration) -> Self {
+        let state = current_clock_state();
+        let (deadline, timer_id) = {
+            let mut state = state.borrow_mut();
+            let timer_id = state.next_timer_id;
+            state.next_timer_id += 1;
+            (state.elapsed.saturating_add(duration), timer_id)
+        };
+        Self {
+            state,
+            deadline,
+            timer_id,
+        }
+    }
+}
+
+impl Clock for VirtualSleep {
+    type Instant = VirtualInstant;
+
+    /// Returns the current time of the current clock of the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`VirtualClock`] has been created on the current thread.
+    fn now() -> Self::Instant {
+        VirtualInstant {
+            elapsed: current_clock_state().borrow().elapsed,
+        }
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.elapsed.saturating_sub(earlier.elapsed)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant
+            .elapsed
+            .checked_add(duration)
+            .map(|elapsed| VirtualInstant { elapsed })
+    }
+}
+
+#[cfg(feature = "std")]
+impl crate::sync::BlockingClock for VirtualSleep {
+    type Instant = VirtualInstant;
+
+    /// Returns the current time of the current clock of the thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`VirtualClock`] has been created on the current thread.
+    fn now() -> Self::Instant {
+        <Self as Clock>::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        <Self as Clock>::saturating_duration_since(instant, earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        <Self as Clock>::checked_add(instant, duration)
+    }
+
+    /// Advances the current clock of the thread until the deadline.
+    ///
+    /// Returns immediately if the deadline has already been reached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no [`VirtualClock`] has been created on the current thread.
+    fn sleep_until(deadline: Self::Instant) {
+        let clock = VirtualClock {
+            state: current_clock_state(),
+        };
+        clock.advance(deadline.elapsed.saturating_sub(clock.elapsed()));
+    }
+}
+
+impl ThrottleInterval for VirtualSleep {
+    type IntervalThrottler<T> = SleepIntervalThrottler<Self, T>;
+
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T> {
+        SleepIntervalThrottler::new(config)
+    }
+}
+
+impl Future for VirtualSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        if state.elapsed >= self.deadline {
+            return Poll::Ready(());
+        }
+        state
+            .timers
+            .insert((self.deadline, self.timer_id), cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for VirtualSleep {
+    fn drop(&mut self) {
+        self.state
+            .borrow_mut()
+            .timers
+            .remove(&(self.deadline, self.timer_id));
+    }
+}
+
+/// Runs an operator on input items that arrive at the given virtual times.
+///
+/// The `timed_items` are sent at their time in milliseconds and must be
+/// ordered by time. The input stream finishes at `end_millis`. Returns the
+/// output items together with the virtual time in milliseconds when they
+/// have been emitted.
+///
+/// # Panics
+///
+/// Panics if the output stream doesn't finish within a minute (virtual time)
+/// after the input stream has finished.
+#[cfg(test)]
+pub(crate) fn run_timed<T, St>(
+    timed_items: impl IntoIterator<Item = (u64, T)>,
+    end_millis: u64,
+    operator: impl FnOnce(futures::channel::mpsc::UnboundedReceiver<T>) -> St,
+) -> Vec<(u128, St::Item)>
+where
+    T: 'static,
+    St: futures_util::Stream + 'static,
+{
+    use futures::{
+        FutureExt as _, StreamExt as _, channel::mpsc, executor::LocalPool,
+        task::LocalSpawnExt as _,
+    };
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+    const MAX_MILLIS_AFTER_END: u64 = 60_000;
+
+    let clock = VirtualClock::new();
+    let mut pool = LocalPool::new();
+
+    let (tx, rx) = mpsc::unbounded();
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let finished = Rc::new(RefCell::new(false));
+    pool.spawner()
+        .spawn_local({
+            let clock = clock.clone();
+            let output = Rc::clone(&output);
+            let finished = Rc::clone(&finished);
+            operator(rx)
+                .for_each(move |item| {
+                    output
+                        .borrow_mut()
+                        .push((clock.elapsed().as_millis(), item));
+                    async {}
+                })
+                .map(move |()| *finished.borrow_mut() = true)
+        })
+        .unwrap();
+
+    let mut timed_items = timed_items.into_iter().peekable();
+    let mut tx = Some(tx);
+    for millis in 0..=end_millis.saturating_add(MAX_MILLIS_AFTER_END) {
+        while let Some((_, item)) = timed_items.next_if(|(at_millis, _)| *at_millis <= millis) {
+            tx.as_ref()
+                .expect("items are sent before the input stream finishes")
+                .unbounded_send(item)
+                .unwrap();
+        }
+        if millis == end_millis {
+            tx = None;
+        }
+        pool.run_until_stalled();
+        if *finished.borrow() {
+            return output.take();
+        }
+        clock.advance(TIME_TICK);
+    }
+    panic!("output stream has not finished");
+}
+
+/// Debounces bursts of items that are separated by pauses in real time.
+///
+/// Returns the debounced items. Only the last item of each burst is
+/// expected.
+#[cfg(all(test, any(feature = "async-io", feature = "futures-timer")))]
+pub(crate) fn debounce_bursts<S: Sleep>() -> Vec<i32> {
+    use std::num::NonZeroUsize;
+
+    use futures::{StreamExt as _, executor::block_on, stream};
+
+    use crate::StreamExt as _;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    let debounce_delay = TIME_TICK.saturating_mul(10);
+    let burst_pause = TIME_TICK.saturating_mul(50);
+    let bursts = stream::iter([vec![0, 1, 2], vec![3, 4], vec![5]]);
+    let items = bursts.enumerate().flat_map(move |(i, burst)| {
+        let pause = if i == 0 { Duration::ZERO } else { burst_pause };
+        stream::once(S::sleep(pause)).flat_map(move |_| stream::iter(burst.clone()))
+    });
+    block_on(
+        items
+            .debounce::<S>(debounce_delay, NonZeroUsize::MIN)
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, num::NonZeroUsize, rc::Rc, time::Duration};
+
+    use futures::{
+        StreamExt as _,
+        channel::mpsc,
+        executor::LocalPool,
+        future::FutureExt as _,
+        stream,
+        task::{LocalSpawnExt as _, noop_waker_ref},
+    };
+
+    use crate::{Clock, IntervalEdge, Sleep as _, StreamExt as _, ThrottleIntervalConfig};
+
+    use super::{VirtualClock, VirtualSleep};
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn sleep_should_only_fire_after_advancing_the_clock() {
+        let clock = VirtualClock::new();
+        let mut cx = std::task::Context::from_waker(noop_waker_ref());
+        let mut sleep = VirtualSleep::sleep(TIME_TICK.saturating_mul(2));
+        assert!(sleep.poll_unpin(&mut cx).is_pending());
+        clock.advance(TIME_TICK);
+        assert!(sleep.poll_unpin(&mut cx).is_pending());
+        clock.advance(TIME_TICK);
+        assert!(sleep.poll_unpin(&mut cx).is_ready());
+        assert_eq!(TIME_TICK.saturating_mul(2), clock.elapsed());
+    }
+
+    #[test]
+    fn clock_should_measure_virtual_time() {
+        let clock = VirtualClock::new();
+        let started_at = VirtualSleep::now();
+        assert_eq!(clock.now(), started_at);
+        clock.advance(TIME_TICK.saturating_mul(3));
+        let now = VirtualSleep::now();
+        assert_eq!(
+            TIME_TICK.saturating_mul(3),
+            VirtualSleep::saturating_duration_since(now, started_at)
+        );
+        assert_eq!(
+            Duration::ZERO,
+            VirtualSleep::saturating_duration_since(started_at, now)
+        );
+        assert_eq!(
+            Some(now),
+            VirtualSleep::checked_add(started_at, TIME_TICK.saturating_mul(3))
+        );
+    }
+
+    #[test]
+    fn sleep_with_zero_duration_should_be_ready_immediately() {
+        let _clock = VirtualClock::new();
+        let mut cx = std::task::Context::from_waker(noop_waker_ref());
+        assert!(
+            VirtualSleep::sleep(Duration::ZERO)
+                .poll_unpin(&mut cx)
+                .is_ready()
+        );
+    }
+
+    #[test]
+    fn debounce() {
+        let clock = VirtualClock::new();
+        let mut pool = LocalPool::new();
+
+        let (tx, rx) = mpsc::unbounded();
+        let output = Rc::new(RefCell::new(Vec::new()));
+        pool.spawner()
+            .spawn_local({
+                let clock = clock.clone();
+                let output = Rc::clone(&output);
+                rx.debounce::<VirtualSleep>(TIME_TICK.saturating_mul(10), NonZeroUsize::MIN)
+                    .for_each(move |item| {
+                        output
+                            .borrow_mut()
+                            .push((clock.elapsed().as_millis(), item));
+                        async {}
+                    })
+            })
+            .unwrap();
+
+        tx.unbounded_send(0).unwrap();
+        tx.unbounded_send(1).unwrap();
+        pool.run_until_stalled();
+        clock.advance(TIME_TICK.saturating_mul(5));
+        pool.run_until_stalled();
+        tx.unbounded_send(2).unwrap();
+        pool.run_until_stalled();
+        clock.advance(TIME_TICK.saturating_mul(10));
+        pool.run_until_stalled();
+        tx.unbounded_send(3).unwrap();
+        pool.run_until_stalled();
+        clock.advance(TIME_TICK.saturating_mul(20));
+        pool.run_until_stalled();
+
+        assert_eq!(vec![(15, 2), (35, 3)], *output.borrow());
+    }
+
+    #[test]
+    fn debounce_should_not_poll_an_input_stream_that_is_always_ready_forever() {
+        let clock = VirtualClock::new();
+        let mut debounced =
+            stream::iter(0..).debounce::<VirtualSleep>(TIME_TICK, NonZeroUsize::new(10).unwrap());
+        let mut cx = std::task::Context::from_waker(noop_waker_ref());
+        for _ in 0..10 {
+            assert!(debounced.poll_next_unpin(&mut cx).is_pending());
+            clock.advance(TIME_TICK);
+        }
+    }
+
+    #[test]
+    fn throttle_interval() {
+        let clock = VirtualClock::new();
+        let mut pool = LocalPool::new();
+
+        let (tx, rx) = mpsc::unbounded();
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let config = ThrottleIntervalConfig {
+            period: TIME_TICK.saturating_mul(10),
+            edge: IntervalEdge::Leading,
+        };
+        pool.spawner()
+            .spawn_local({
+                let clock = clock.clone();
+                let output = Rc::clone(&output);
+                rx.throttle_interval::<VirtualSleep>(config, NonZeroUsize::MIN)
+                    .for_each(move |item| {
+                        output
+                            .borrow_mut()
+                            .push((clock.elapsed().as_millis(), item));
+                        async {}
+                    })
+            })
+            .unwrap();
+
+        for item in 0..30 {
+            tx.unbounded_send(item).unwrap();
+            pool.run_until_stalled();
+            clock.advance(TIME_TICK.saturating_mul(3));
+            pool.run_until_stalled();
+        }
+
+        assert_eq!(
+            vec![(0, 0), (12, 3), (21, 6), (30, 9), (42, 13), (51, 16)],
+            output.borrow()[..6]
+        );
+    }
+}
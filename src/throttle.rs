@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: The futures-stream-ext authors
 // SPDX-License-Identifier: MPL-2.0
 
-use std::{
+use core::{
     num::NonZeroUsize,
     pin::Pin,
     task::{Context, Poll, ready},
@@ -11,7 +11,15 @@ use std::{
 use futures_util::stream::Stream;
 use pin_project_lite::pin_project;
 
-use crate::IntervalEdge;
+use crate::{
+    IntervalEdge, Sleep,
+    budget::{ReadyBudget, ReadyItems},
+};
+
+mod interval;
+pub use self::interval::SleepIntervalThrottler;
+#[cfg(feature = "std")]
+pub(crate) use self::interval::{InstantArithmetic, next_deadline};
 
 /// Callbacks for throttling a stream
 pub trait Throttler<T>: Stream<Item = ()> {
@@ -52,7 +60,7 @@ pin_project! {
         stream: S,
         #[pin]
         throttler: T,
-        poll_next_max_ready_count: NonZeroUsize,
+        ready_budget: ReadyBudget,
         state: State,
         pending: Option<S::Item>,
     }
@@ -64,10 +72,31 @@ where
     T: Throttler<<S as Stream>::Item>,
 {
     pub const fn new(stream: S, throttler: T, poll_next_max_ready_count: NonZeroUsize) -> Self {
+        Self::with_ready_budget(
+            stream,
+            throttler,
+            ReadyBudget::MaxCount(poll_next_max_ready_count),
+        )
+    }
+
+    /// Creates a throttled stream that respects the cooperative
+    /// scheduling budget of tokio.
+    ///
+    /// The input stream is polled while the budget of the current task
+    /// allows instead of up to a fixed number of items.
+    ///
+    /// See also: [`StreamExt::throttle_coop()`](crate::StreamExt::throttle_coop)
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub const fn with_coop_budget(stream: S, throttler: T) -> Self {
+        Self::with_ready_budget(stream, throttler, ReadyBudget::TokioCoop)
+    }
+
+    const fn with_ready_budget(stream: S, throttler: T, ready_budget: ReadyBudget) -> Self {
         Self {
             stream,
             throttler,
-            poll_next_max_ready_count,
+            ready_budget,
             state: State::Streaming,
             pending: None,
         }
@@ -87,33 +116,16 @@ where
         if matches!(this.state, State::Streaming) {
             // Poll the inner stream while it yields items. We want to receive
             // the most recent item that is ready.
-            let mut ready_count = 0;
-            loop {
-                match this.stream.as_mut().poll_next(cx) {
-                    Poll::Ready(Some(item)) => {
-                        if this.pending.is_none() {
-                            this.throttler.as_mut().throttle_pending(cx);
-                        }
-                        *this.pending = Some(item);
-                        debug_assert!(ready_count < this.poll_next_max_ready_count.get());
-                        ready_count += 1;
-                        if ready_count >= this.poll_next_max_ready_count.get() {
-                            // Stop polling the inner stream to prevent endless loops
-                            // for streams that are always ready.
-                            // Wake ourselves up to ensure that polling the stream continues after
-                            // after polling the throttler.
-                            cx.waker().wake_by_ref();
-                            break;
-                        }
-                    }
-                    Poll::Ready(None) => {
-                        *this.state = State::Finishing;
-                        break;
-                    }
-                    Poll::Pending => {
-                        break;
+            let ready_budget = *this.ready_budget;
+            let ready_items =
+                ready_budget.poll_ready_items(this.stream.as_mut(), cx, |cx, item| {
+                    if this.pending.is_none() {
+                        this.throttler.as_mut().throttle_pending(cx);
                     }
-                }
+                    *this.pending = Some(item);
+                });
+            if ready_items == ReadyItems::Finished {
+                *this.state = State::Finishing;
             }
         }
 
@@ -150,6 +162,15 @@ where
     }
 }
 
+/// [`Sleep`] implementation that provides a [`Throttler`] with a fixed interval.
+pub trait ThrottleInterval: Sleep {
+    /// Interval throttler for items of type `T`.
+    type IntervalThrottler<T>: Throttler<T>;
+
+    /// Creates a new interval throttler.
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T>;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ThrottleIntervalConfig {
     /// Throttling period
@@ -164,3 +185,48 @@ pub struct ThrottleIntervalConfig {
     /// immediately or after the interval has elapsed.
     pub edge: IntervalEdge,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use futures::{StreamExt as _, executor::block_on, stream};
+    use futures_util::Stream;
+
+    use crate::{StreamExt as _, Throttler};
+
+    /// Yields all pending items immediately.
+    #[derive(Debug)]
+    struct PassThroughThrottler;
+
+    impl Stream for PassThroughThrottler {
+        type Item = ();
+
+        fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(Some(()))
+        }
+    }
+
+    impl<T> Throttler<T> for PassThroughThrottler {
+        fn throttle_pending(self: Pin<&mut Self>, _cx: &mut Context<'_>) {}
+
+        fn throttle_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>, _next_item: Option<&T>) {}
+    }
+
+    #[test]
+    fn should_skip_ready_items_with_a_custom_throttler() {
+        let poll_next_max_ready_count = NonZeroUsize::new(3).unwrap();
+        assert_eq!(
+            vec![2, 5, 8, 9],
+            block_on(
+                stream::iter(0..10)
+                    .throttle(PassThroughThrottler, poll_next_max_ready_count)
+                    .collect::<Vec<_>>()
+            )
+        );
+    }
+}
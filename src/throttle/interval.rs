@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use core::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::{Clock, IntervalEdge, ThrottleIntervalConfig, Throttler, restart_sleep};
+
+#[derive(Debug, Clone, Copy)]
+enum IntervalThrottlerState {
+    Idle,
+    Pending,
+}
+
+pin_project! {
+    /// Interval throttler for any [`Sleep`](crate::Sleep) implementation.
+    ///
+    /// The deadlines of the interval are calculated from the [`Clock`]
+    /// and don't drift. Missed ticks are skipped.
+    #[derive(Debug)]
+    #[project = SleepIntervalThrottlerProjection]
+    pub struct SleepIntervalThrottler<S: Clock, T> {
+        config: ThrottleIntervalConfig,
+        // Kept after it has elapsed to be restarted for the next deadline.
+        #[pin]
+        sleep: Option<S>,
+        sleep_armed: bool,
+        // `None` if the next deadline is not representable.
+        deadline: Option<S::Instant>,
+        state: IntervalThrottlerState,
+        _marker: PhantomData<T>,
+    }
+}
+
+impl<S: Clock, T> SleepIntervalThrottler<S, T> {
+    #[must_use]
+    pub const fn new(config: ThrottleIntervalConfig) -> Self {
+        Self {
+            config,
+            sleep: None,
+            sleep_armed: false,
+            deadline: None,
+            state: IntervalThrottlerState::Idle,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Arithmetic of points in time for calculating deadlines.
+///
+/// Shared by the [`Clock`] of asynchronous and the
+/// [`BlockingClock`](crate::sync::BlockingClock) of blocking throttlers.
+pub(crate) trait InstantArithmetic {
+    type Instant: Copy + Ord;
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration;
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant>;
+}
+
+impl<S: Clock> InstantArithmetic for S {
+    type Instant = S::Instant;
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        <S as Clock>::saturating_duration_since(instant, earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        <S as Clock>::checked_add(instant, duration)
+    }
+}
+
+/// Calculates the deadline that follows the elapsed `deadline`.
+///
+/// Missed ticks are skipped while keeping the deadlines aligned
+/// with the period.
+pub(crate) fn next_deadline<A: InstantArithmetic>(
+    deadline: A::Instant,
+    period: Duration,
+    now: A::Instant,
+) -> Option<A::Instant> {
+    let next_deadline = A::checked_add(deadline, period)?;
+    if next_deadline > now {
+        return Some(next_deadline);
+    }
+    let elapsed_nanos = A::saturating_duration_since(now, deadline).as_nanos();
+    // The remainder is less than the period and always fits into `u64`
+    // for all practical periods.
+    #[expect(clippy::cast_possible_truncation)]
+    let overdue = Duration::from_nanos((elapsed_nanos % period.as_nanos()) as u64);
+    A::checked_add(now, period.saturating_sub(overdue))
+}
+
+impl<S: Clock, T> Stream for SleepIntervalThrottler<S, T> {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let SleepIntervalThrottlerProjection {
+            config: ThrottleIntervalConfig { period, edge: _ },
+            mut sleep,
+            sleep_armed,
+            deadline,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => Poll::Pending,
+            IntervalThrottlerState::Pending => {
+                if period.is_zero() {
+                    return Poll::Ready(Some(()));
+                }
+                let Some(current_deadline) = *deadline else {
+                    // Never ready.
+                    return Poll::Pending;
+                };
+                let now = S::now();
+                if now < current_deadline {
+                    if !*sleep_armed {
+                        let timeout = S::saturating_duration_since(current_deadline, now);
+                        restart_sleep(sleep.as_mut(), timeout);
+                        *sleep_armed = true;
+                    }
+                    let poll_sleep = sleep.as_mut().as_pin_mut().expect("some");
+                    ready!(poll_sleep.poll(cx));
+                }
+                // The deadline has elapsed.
+                *sleep_armed = false;
+                *deadline = next_deadline::<S>(current_deadline, *period, S::now());
+                Poll::Ready(Some(()))
+            }
+        }
+    }
+}
+
+impl<S: Clock, T> Throttler<T> for SleepIntervalThrottler<S, T> {
+    fn throttle_pending(self: Pin<&mut Self>, _cx: &mut Context<'_>) {
+        let SleepIntervalThrottlerProjection {
+            config: ThrottleIntervalConfig { period, edge },
+            sleep: _,
+            sleep_armed,
+            deadline,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => {
+                *state = IntervalThrottlerState::Pending;
+                let now = S::now();
+                *deadline = match edge {
+                    IntervalEdge::Leading => Some(now),
+                    IntervalEdge::Trailing => S::checked_add(now, *period),
+                };
+                // The sleep is restarted on demand when polled.
+                *sleep_armed = false;
+            }
+            IntervalThrottlerState::Pending => (),
+        }
+    }
+
+    fn throttle_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>, next_item: Option<&T>) {
+        let SleepIntervalThrottlerProjection {
+            config: _,
+            mut sleep,
+            sleep_armed,
+            deadline: _,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => unreachable!(),
+            IntervalThrottlerState::Pending => {
+                if next_item.is_none() {
+                    *state = IntervalThrottlerState::Idle;
+                    if *sleep_armed {
+                        // Cancel the pending sleep.
+                        sleep.set(None);
+                        *sleep_armed = false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        Clock as _,
+        testing::{VirtualClock, VirtualSleep},
+    };
+
+    use super::next_deadline;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn next_deadline_should_skip_missed_ticks_aligned_with_the_period() {
+        let _clock = VirtualClock::new();
+        let deadline = VirtualSleep::now();
+        let period = TIME_TICK.saturating_mul(10);
+        let after = |ticks| VirtualSleep::checked_add(deadline, TIME_TICK.saturating_mul(ticks));
+        assert_eq!(
+            after(10),
+            next_deadline::<VirtualSleep>(deadline, period, deadline)
+        );
+        assert_eq!(
+            after(10),
+            next_deadline::<VirtualSleep>(deadline, period, after(9).unwrap())
+        );
+        assert_eq!(
+            after(20),
+            next_deadline::<VirtualSleep>(deadline, period, after(10).unwrap())
+        );
+        assert_eq!(
+            after(30),
+            next_deadline::<VirtualSleep>(deadline, period, after(25).unwrap())
+        );
+        let late_deadline = after(10).unwrap();
+        assert_eq!(
+            None,
+            next_deadline::<VirtualSleep>(late_deadline, Duration::MAX, late_deadline)
+        );
+    }
+}
@@ -1,37 +1,76 @@
 // SPDX-FileCopyrightText: The futures-stream-ext authors
 // SPDX-License-Identifier: MPL-2.0
 
-use std::time::Duration;
+use std::{pin::Pin, time::Duration};
 
-use futures_util::Stream;
-use tokio::time::Sleep;
-
-use crate::{StreamExt, ThrottleIntervalConfig, Throttled};
+use crate::{ThrottleInterval, ThrottleIntervalConfig};
 
 mod debounce;
 
+mod spawn;
+pub use self::spawn::{SpawnDriven, spawn_driven};
+
 mod throttle;
 pub use self::throttle::IntervalThrottler;
 
+#[cfg(all(feature = "timerfd", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "timerfd", target_os = "linux"))))]
+pub mod timerfd;
+
 impl crate::Sleep for tokio::time::Sleep {
     fn sleep(duration: Duration) -> Self {
         tokio::time::sleep(duration)
     }
+
+    fn reset(mut self: Pin<&mut Self>, duration: Duration) {
+        if let Some(deadline) = tokio::time::Instant::now().checked_add(duration) {
+            tokio::time::Sleep::reset(self, deadline);
+        } else {
+            // The deadline is not representable and never reached.
+            self.set(tokio::time::sleep(duration));
+        }
+    }
+}
+
+impl crate::Clock for tokio::time::Sleep {
+    type Instant = tokio::time::Instant;
+
+    fn now() -> Self::Instant {
+        tokio::time::Instant::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.saturating_duration_since(earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant.checked_add(duration)
+    }
+}
+
+impl ThrottleInterval for tokio::time::Sleep {
+    type IntervalThrottler<T> = IntervalThrottler<T>;
+
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T> {
+        IntervalThrottler::new(config)
+    }
 }
 
-impl<S: Stream> StreamExt for S {
-    type Sleep = Sleep;
-    type IntervalThrottler = IntervalThrottler<S::Item>;
-
-    fn throttle_interval(
-        self,
-        config: ThrottleIntervalConfig,
-        poll_next_max_ready_count: std::num::NonZeroUsize,
-    ) -> Throttled<Self, Self::IntervalThrottler>
-    where
-        Self: Sized,
-    {
-        let throttler = IntervalThrottler::new(config);
-        self.throttle(throttler, poll_next_max_ready_count)
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::{self, Sleep};
+
+    use crate::Clock;
+
+    #[tokio::test(start_paused = true)]
+    async fn clock_should_follow_the_paused_time() {
+        let started_at = Sleep::now();
+        time::advance(Duration::from_millis(5)).await;
+        assert_eq!(
+            Duration::from_millis(5),
+            Sleep::saturating_duration_since(Sleep::now(), started_at)
+        );
     }
 }
@@ -3,15 +3,15 @@
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{num::NonZeroUsize, time::Duration};
 
     use futures::{Stream, StreamExt as _, stream};
     use tokio::{
         runtime,
-        time::{self, Instant, sleep_until},
+        time::{self, Instant, Sleep, sleep_until},
     };
 
-    use crate::StreamExt;
+    use crate::{DebounceConfig, DebounceEdge, StreamExt};
 
     const TIME_TICK: Duration = Duration::from_millis(1);
 
@@ -40,7 +40,7 @@ mod tests {
             let started_at = Instant::now();
             let join_handle = rt_handle.spawn(
                 periodic_stream(started_at, item_period)
-                    .debounce(debounce_delay)
+                    .debounce::<Sleep>(debounce_delay, NonZeroUsize::MIN)
                     .map(move |item| ((Instant::now() - started_at).as_millis(), item))
                     .take(num_items)
                     .collect::<Vec<_>>(),
@@ -74,5 +74,179 @@ mod tests {
         );
     }
 
+    fn run_timed_stream_debounced(
+        timed_items: impl IntoIterator<Item = (u32, usize)>,
+        config: DebounceConfig,
+    ) -> Vec<(u128, usize)> {
+        let timed_items = timed_items.into_iter().collect::<Vec<_>>();
+        let rt = runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        rt.block_on(async move {
+            let started_at = Instant::now();
+            stream::iter(timed_items)
+                .then(move |(millis, item)| async move {
+                    sleep_until(started_at + TIME_TICK.saturating_mul(millis)).await;
+                    item
+                })
+                .debounce::<Sleep>(config, NonZeroUsize::MIN)
+                .map(move |item| ((Instant::now() - started_at).as_millis(), item))
+                .collect::<Vec<_>>()
+                .await
+        })
+    }
+
+    fn run_bursts_debounced(config: DebounceConfig) -> Vec<(u128, usize)> {
+        // ms:   0 | 2 | 4 | 30 | 32 | 60
+        // item: 0 | 1 | 2 |  3 |  4 |  5
+        run_timed_stream_debounced([(0, 0), (2, 1), (4, 2), (30, 3), (32, 4), (60, 5)], config)
+    }
+
+    #[test]
+    fn debounce_edges() {
+        let delay = TIME_TICK.saturating_mul(10);
+        assert_eq!(
+            vec![(14, 2), (42, 4), (70, 5)],
+            run_bursts_debounced(DebounceConfig {
+                delay,
+                edge: DebounceEdge::Trailing,
+                max_wait: None,
+            })
+        );
+        assert_eq!(
+            vec![(0, 0), (30, 3), (60, 5)],
+            run_bursts_debounced(DebounceConfig {
+                delay,
+                edge: DebounceEdge::Leading,
+                max_wait: None,
+            })
+        );
+        assert_eq!(
+            vec![(0, 0), (14, 2), (30, 3), (42, 4), (60, 5)],
+            run_bursts_debounced(DebounceConfig {
+                delay,
+                edge: DebounceEdge::Both,
+                max_wait: None,
+            })
+        );
+    }
+
+    #[test]
+    fn debounce_with_max_wait() {
+        // ms:   0 | 4 | 8 | 12 | 16 | 20 | 24 | 28 | 32 | 36 | 40
+        // item: 0 | 1 | 2 |  3 |  4 |  5 |  6 |  7 |  8 |  9 | 10
+        let timed_items = (0..=10).map(|i| (4 * u32::try_from(i).unwrap(), i));
+        let delay = TIME_TICK.saturating_mul(10);
+        let max_wait = Some(TIME_TICK.saturating_mul(15));
+        assert_eq!(
+            vec![(15, 3), (31, 7), (47, 10)],
+            run_timed_stream_debounced(
+                timed_items.clone(),
+                DebounceConfig {
+                    delay,
+                    edge: DebounceEdge::Trailing,
+                    max_wait,
+                }
+            )
+        );
+        assert_eq!(
+            vec![(0, 0), (16, 4), (32, 8)],
+            run_timed_stream_debounced(
+                timed_items.clone(),
+                DebounceConfig {
+                    delay,
+                    edge: DebounceEdge::Leading,
+                    max_wait,
+                }
+            )
+        );
+        assert_eq!(
+            vec![(0, 0), (15, 3), (16, 4), (31, 7), (32, 8), (47, 10)],
+            run_timed_stream_debounced(
+                timed_items,
+                DebounceConfig {
+                    delay,
+                    edge: DebounceEdge::Both,
+                    max_wait,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn debounce_without_max_wait_should_wait_for_a_quiet_period() {
+        let timed_items = (0..=10).map(|i| (4 * u32::try_from(i).unwrap(), i));
+        assert_eq!(
+            vec![(50, 10)],
+            run_timed_stream_debounced(timed_items, TIME_TICK.saturating_mul(10).into())
+        );
+    }
+
+    #[test]
+    fn debounce_by_item() {
+        // Odd items are final and debounced with a shorter delay.
+        let timed_items = [(0, 0), (4, 1), (20, 2), (30, 3), (40, 4)];
+        let rt = runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        let items = rt.block_on(async move {
+            let started_at = Instant::now();
+            stream::iter(timed_items)
+                .then(move |(millis, item)| async move {
+                    sleep_until(started_at + TIME_TICK.saturating_mul(millis)).await;
+                    item
+                })
+                .debounce_by::<Sleep, _>(
+                    |item| {
+                        if item % 2 == 1 {
+                            TIME_TICK
+                        } else {
+                            TIME_TICK.saturating_mul(15)
+                        }
+                    },
+                    NonZeroUsize::MIN,
+                )
+                .map(move |item| ((Instant::now() - started_at).as_millis(), item))
+                .collect::<Vec<_>>()
+                .await
+        });
+        assert_eq!(vec![(5, 1), (31, 3), (55, 4)], items);
+    }
+
+    #[tokio::test]
+    async fn should_yield_to_other_tasks_for_an_input_stream_that_is_always_ready() {
+        let join_handle = tokio::spawn(
+            stream::iter(0..)
+                .debounce::<Sleep>(TIME_TICK, NonZeroUsize::new(100).unwrap())
+                .for_each(|_| async {}),
+        );
+        // Would never return if the spawned task was not yielding.
+        tokio::task::yield_now().await;
+        join_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn should_yield_to_other_tasks_with_coop_budget() {
+        let join_handle = tokio::spawn(
+            stream::iter(0..)
+                .debounce_coop::<Sleep>(TIME_TICK)
+                .for_each(|_| async {}),
+        );
+        // Would never return if the spawned task was not yielding.
+        tokio::task::yield_now().await;
+        join_handle.abort();
+        assert_eq!(
+            vec![999],
+            stream::iter(0..1000)
+                .debounce_coop::<Sleep>(TIME_TICK)
+                .collect::<Vec<_>>()
+                .await
+        );
+    }
+
     // TODO: Add more tests, especially for edge cases.
 }
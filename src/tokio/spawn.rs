@@ -0,0 +1,171 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+    num::NonZeroUsize,
+    panic,
+    pin::{Pin, pin},
+    task::{Context, Poll, ready},
+};
+
+use futures_util::{Stream, StreamExt as _};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// Drives a stream in a spawned task.
+///
+/// The spawned task polls the stream independently of the consumer.
+/// Timers of [`Debounced`](crate::Debounced) or [`Throttled`](crate::Throttled)
+/// streams are then no longer affected by the latency of the consumer.
+///
+/// Items are handed over to the consumer through a channel that buffers up
+/// to `capacity` items. No items are lost. If the buffer is full the task
+/// waits for the consumer and stops polling the stream, i.e. the consumer
+/// then affects the timers again.
+///
+/// The task is aborted when the returned stream is dropped. Panics of the
+/// task are propagated to the consumer.
+///
+/// # Panics
+///
+/// Panics if not called from within a tokio runtime.
+pub fn spawn_driven<St>(stream: St, capacity: NonZeroUsize) -> SpawnDriven<St::Item>
+where
+    St: Stream + Send + 'static,
+    St::Item: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(capacity.get());
+    let join_handle = tokio::spawn(async move {
+        let mut stream = pin!(stream);
+        while let Some(item) = stream.next().await {
+            if tx.send(item).await.is_err() {
+                // The consumer has been dropped.
+                break;
+            }
+        }
+    });
+    SpawnDriven {
+        rx,
+        join_handle: Some(join_handle),
+    }
+}
+
+/// Result of [`spawn_driven()`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled or .awaited"]
+pub struct SpawnDriven<T> {
+    rx: mpsc::Receiver<T>,
+    // `None` after the task has finished.
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl<T> Stream for SpawnDriven<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(item) = ready!(self.rx.poll_recv(cx)) {
+            return Poll::Ready(Some(item));
+        }
+        // All items have been received after the task has dropped the sender.
+        let Some(join_handle) = &mut self.join_handle else {
+            // Finished.
+            return Poll::Ready(None);
+        };
+        let Poll::Ready(result) = Pin::new(join_handle).poll(cx) else {
+            return Poll::Pending;
+        };
+        self.join_handle = None;
+        if let Err(err) = result
+            && err.is_panic()
+        {
+            panic::resume_unwind(err.into_panic());
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl<T> Drop for SpawnDriven<T> {
+    fn drop(&mut self) {
+        if let Some(join_handle) = &self.join_handle {
+            join_handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroUsize, time::Duration};
+
+    use futures::{StreamExt as _, channel::mpsc, stream};
+    use tokio::time::{Instant, Sleep, sleep, sleep_until};
+
+    use crate::StreamExt as _;
+
+    use super::spawn_driven;
+
+    const TIME_TICK: Duration = Duration::from_millis(1);
+
+    #[tokio::test(start_paused = true)]
+    async fn should_not_distort_debounce_windows_by_a_slow_consumer() {
+        let started_at = Instant::now();
+        let input =
+            stream::iter([(0, 0), (5, 1), (30, 2)]).then(move |(millis, item)| async move {
+                sleep_until(started_at + TIME_TICK.saturating_mul(millis)).await;
+                item
+            });
+        let mut debounced = spawn_driven(
+            input
+                .debounce::<Sleep>(TIME_TICK.saturating_mul(10), NonZeroUsize::MIN)
+                .map(move |item| ((Instant::now() - started_at).as_millis(), item)),
+            NonZeroUsize::MIN,
+        );
+        assert_eq!(Some((15, 1)), debounced.next().await);
+        // The consumer is busy while the next item arrives.
+        sleep(TIME_TICK.saturating_mul(20)).await;
+        assert_eq!(Some((40, 2)), debounced.next().await);
+        assert_eq!(None, debounced.next().await);
+    }
+
+    #[tokio::test]
+    async fn should_yield_the_last_item_before_finishing() {
+        assert_eq!(
+            vec![2],
+            spawn_driven(
+                stream::iter(0..3).debounce::<Sleep>(TIME_TICK, NonZeroUsize::MIN),
+                NonZeroUsize::MIN
+            )
+            .collect::<Vec<_>>()
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn should_not_lose_items_of_a_slow_consumer() {
+        let mut driven = spawn_driven(stream::iter(0..10), NonZeroUsize::MIN);
+        let mut items = Vec::new();
+        while let Some(item) = driven.next().await {
+            items.push(item);
+            // The consumer is busy while the task waits for it.
+            tokio::task::yield_now().await;
+        }
+        assert_eq!((0..10).collect::<Vec<_>>(), items);
+    }
+
+    #[tokio::test]
+    async fn should_stop_the_task_when_dropped() {
+        let (tx, rx) = mpsc::unbounded::<()>();
+        drop(spawn_driven(rx, NonZeroUsize::MIN));
+        // The input stream is dropped after the aborted task has been cancelled.
+        while !tx.is_closed() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic = "input stream panicked"]
+    async fn should_propagate_panics_of_the_task() {
+        let input = stream::iter([false]).inspect(|&ok| assert!(ok, "input stream panicked"));
+        spawn_driven(input, NonZeroUsize::MIN)
+            .collect::<Vec<_>>()
+            .await;
+    }
+}
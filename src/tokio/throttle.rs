@@ -128,10 +128,14 @@ mod tests {
     use futures::{Stream, StreamExt as _, stream};
     use tokio::{
         runtime,
-        time::{self, Instant, sleep_until},
+        time::{self, Instant, Sleep, sleep_until},
     };
 
-    use crate::{IntervalEdge, StreamExt as _, ThrottleIntervalConfig};
+    use crate::{
+        IntervalEdge, SleepIntervalThrottler, StreamExt as _, ThrottleIntervalConfig, Throttler,
+    };
+
+    use super::IntervalThrottler;
 
     const TIME_TICK: Duration = Duration::from_millis(1);
 
@@ -154,12 +158,16 @@ mod tests {
         })
     }
 
-    fn run_alternating_delay_stream(
+    fn run_alternating_delay_stream_with<T>(
+        new_throttler: fn(ThrottleIntervalConfig) -> T,
         config: ThrottleIntervalConfig,
         first_delay: Duration,
         second_delay: Duration,
         num_items: usize,
-    ) -> Vec<(u128, usize)> {
+    ) -> Vec<(u128, usize)>
+    where
+        T: Throttler<usize> + Send + 'static,
+    {
         let rt = runtime::Builder::new_current_thread()
             .enable_time()
             .start_paused(true)
@@ -170,7 +178,7 @@ mod tests {
             let started_at = Instant::now();
             let join_handle = rt_handle.spawn(
                 alternating_delay_stream(started_at, first_delay, second_delay)
-                    .throttle_interval(config, NonZeroUsize::MIN)
+                    .throttle(new_throttler(config), NonZeroUsize::MIN)
                     .map(move |item| ((Instant::now() - started_at).as_millis(), item))
                     .take(num_items)
                     .collect::<Vec<_>>(),
@@ -182,6 +190,33 @@ mod tests {
         })
     }
 
+    fn run_alternating_delay_stream(
+        config: ThrottleIntervalConfig,
+        first_delay: Duration,
+        second_delay: Duration,
+        num_items: usize,
+    ) -> Vec<(u128, usize)> {
+        let items = run_alternating_delay_stream_with(
+            IntervalThrottler::new,
+            config,
+            first_delay,
+            second_delay,
+            num_items,
+        );
+        // The runtime-agnostic throttler must behave like the tokio throttler.
+        assert_eq!(
+            items,
+            run_alternating_delay_stream_with(
+                SleepIntervalThrottler::<Sleep, _>::new,
+                config,
+                first_delay,
+                second_delay,
+                num_items,
+            )
+        );
+        items
+    }
+
     #[test]
     fn should_pass_through_an_input_stream_that_is_always_ready_with_an_empty_period() {
         let first_delay = Duration::ZERO;
@@ -362,7 +397,17 @@ mod tests {
                 assert_eq!(
                     Vec::<()>::new(),
                     futures::stream::empty::<()>()
-                        .throttle_interval(config, NonZeroUsize::MIN)
+                        .throttle_interval::<Sleep>(config, NonZeroUsize::MIN)
+                        .collect::<Vec<_>>()
+                        .await
+                );
+                assert_eq!(
+                    Vec::<()>::new(),
+                    futures::stream::empty::<()>()
+                        .throttle(
+                            SleepIntervalThrottler::<Sleep, _>::new(config),
+                            NonZeroUsize::MIN
+                        )
                         .collect::<Vec<_>>()
                         .await
                 );
@@ -378,11 +423,47 @@ mod tests {
                 assert_eq!(
                     &[()],
                     futures::stream::once(async {})
-                        .throttle_interval(config, NonZeroUsize::MIN)
+                        .throttle_interval::<Sleep>(config, NonZeroUsize::MIN)
                         .collect::<Vec<_>>()
                         .await
                         .as_slice()
                 );
+                assert_eq!(
+                    &[()],
+                    futures::stream::once(async {})
+                        .throttle(
+                            SleepIntervalThrottler::<Sleep, _>::new(config),
+                            NonZeroUsize::MIN
+                        )
+                        .collect::<Vec<_>>()
+                        .await
+                        .as_slice()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn should_yield_to_other_tasks_with_coop_budget() {
+        for period in [Duration::ZERO, TIME_TICK] {
+            for edge in [IntervalEdge::Leading, IntervalEdge::Trailing] {
+                let config = ThrottleIntervalConfig { period, edge };
+                let join_handle = tokio::spawn(
+                    stream::iter(0..)
+                        .throttle_interval_coop::<Sleep>(config)
+                        .for_each(|_| async {}),
+                );
+                // Would never return if the spawned task was not yielding.
+                tokio::task::yield_now().await;
+                join_handle.abort();
+                assert_eq!(
+                    3,
+                    stream::iter(0..)
+                        .throttle_interval_coop::<Sleep>(config)
+                        .take(3)
+                        .count()
+                        .await
+                );
             }
         }
     }
@@ -0,0 +1,425 @@
+// SPDX-FileCopyrightText: The futures-stream-ext authors
+// SPDX-License-Identifier: MPL-2.0
+
+//! High-resolution timers based on Linux `timerfd`.
+//!
+//! The timers are registered with the I/O driver of tokio and are not
+//! limited by the millisecond granularity of the tokio timer wheel.
+//! The tokio runtime must have both I/O and time enabled.
+
+use std::{
+    io,
+    marker::PhantomData,
+    os::fd::OwnedFd,
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::{Duration, Instant},
+};
+
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+use rustix::time::{
+    Itimerspec, TimerfdClockId, TimerfdFlags, TimerfdTimerFlags, Timespec, timerfd_create,
+    timerfd_settime,
+};
+use tokio::io::unix::AsyncFd;
+
+use crate::{IntervalEdge, ThrottleInterval, ThrottleIntervalConfig, Throttler};
+
+fn timespec(duration: Duration) -> Timespec {
+    // Saturate durations that exceed the range of the timer.
+    Timespec::try_from(duration).unwrap_or(Timespec {
+        tv_sec: i64::MAX,
+        tv_nsec: 999_999_999,
+    })
+}
+
+fn create_timerfd() -> io::Result<AsyncFd<OwnedFd>> {
+    let fd = timerfd_create(
+        TimerfdClockId::Monotonic,
+        TimerfdFlags::NONBLOCK | TimerfdFlags::CLOEXEC,
+    )?;
+    AsyncFd::new(fd)
+}
+
+/// Arms or disarms the timer.
+///
+/// A zero `value` disarms the timer. A zero `interval` arms the timer
+/// only once.
+fn set_timerfd(fd: &AsyncFd<OwnedFd>, value: Duration, interval: Duration) -> io::Result<()> {
+    let new_value = Itimerspec {
+        it_interval: timespec(interval),
+        it_value: timespec(value),
+    };
+    timerfd_settime(fd.get_ref(), TimerfdTimerFlags::empty(), &new_value)?;
+    Ok(())
+}
+
+/// Waits until the timer has expired.
+///
+/// Consumes all expirations that occurred since the last invocation.
+fn poll_timerfd_expired(fd: &AsyncFd<OwnedFd>, cx: &mut Context<'_>) -> Poll<()> {
+    loop {
+        let mut guard = ready!(fd.poll_read_ready(cx)).expect("poll timerfd");
+        let mut expirations = [0; 8];
+        if let Ok(result) = guard
+            .try_io(|fd| rustix::io::read(fd.get_ref(), &mut expirations).map_err(io::Error::from))
+        {
+            result.expect("read timerfd");
+            return Poll::Ready(());
+        }
+        // Spurious wakeup, i.e. the timer has not expired yet.
+    }
+}
+
+/// [`Sleep`](crate::Sleep) implementation based on a `timerfd`.
+///
+/// Provides sub-millisecond precision. Restarting the timer re-arms the
+/// same `timerfd`, i.e. a stream uses a single `timerfd` for all of its
+/// sleeps.
+///
+/// # Panics
+///
+/// Polling panics if reading the `timerfd` fails.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TimerFdSleep {
+    // Created lazily for the first non-zero duration.
+    fd: Option<AsyncFd<OwnedFd>>,
+    elapsed: bool,
+}
+
+impl TimerFdSleep {
+    /// Creates and arms a new `timerfd`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` could not be created, registered,
+    /// or armed, e.g. if the process has run out of file descriptors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from within a tokio runtime with I/O enabled.
+    pub fn try_sleep(duration: Duration) -> io::Result<Self> {
+        let mut sleep = Self {
+            fd: None,
+            elapsed: true,
+        };
+        sleep.try_reset(duration)?;
+        Ok(sleep)
+    }
+
+    /// Re-arms the `timerfd` with the given duration.
+    ///
+    /// The `timerfd` is only created if none exists yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `timerfd` could not be created, registered,
+    /// or armed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `timerfd` needs to be created outside of a tokio
+    /// runtime with I/O enabled.
+    pub fn try_reset(&mut self, duration: Duration) -> io::Result<()> {
+        if duration.is_zero() {
+            // A zero value would disarm the timer instead.
+            if let Some(fd) = &self.fd {
+                set_timerfd(fd, Duration::ZERO, Duration::ZERO)?;
+            }
+            self.elapsed = true;
+            return Ok(());
+        }
+        let fd = match &mut self.fd {
+            Some(fd) => fd,
+            fd @ None => fd.insert(create_timerfd()?),
+        };
+        // Arming the timer also discards all previous expirations.
+        set_timerfd(fd, duration, Duration::ZERO)?;
+        self.elapsed = false;
+        Ok(())
+    }
+}
+
+impl crate::Sleep for TimerFdSleep {
+    /// Creates and arms a new `timerfd`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from within a tokio runtime with I/O enabled
+    /// or if [`TimerFdSleep::try_sleep()`] fails.
+    fn sleep(duration: Duration) -> Self {
+        Self::try_sleep(duration).expect("create timerfd")
+    }
+
+    /// Re-arms the existing `timerfd`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`TimerFdSleep::try_reset()`] fails.
+    fn reset(self: Pin<&mut Self>, duration: Duration) {
+        self.get_mut().try_reset(duration).expect("reset timerfd");
+    }
+}
+
+impl crate::Clock for TimerFdSleep {
+    type Instant = Instant;
+
+    fn now() -> Self::Instant {
+        // Both `Instant` and the timer use `CLOCK_MONOTONIC`.
+        Instant::now()
+    }
+
+    fn saturating_duration_since(instant: Self::Instant, earlier: Self::Instant) -> Duration {
+        instant.saturating_duration_since(earlier)
+    }
+
+    fn checked_add(instant: Self::Instant, duration: Duration) -> Option<Self::Instant> {
+        instant.checked_add(duration)
+    }
+}
+
+impl Future for TimerFdSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if this.elapsed {
+            return Poll::Ready(());
+        }
+        let fd = this.fd.as_ref().expect("timerfd armed");
+        ready!(poll_timerfd_expired(fd, cx));
+        // The timer must not be read again until it is re-armed.
+        this.elapsed = true;
+        Poll::Ready(())
+    }
+}
+
+impl ThrottleInterval for TimerFdSleep {
+    type IntervalThrottler<T> = IntervalThrottler<T>;
+
+    fn interval_throttler<T>(config: ThrottleIntervalConfig) -> Self::IntervalThrottler<T> {
+        IntervalThrottler::new(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IntervalThrottlerState {
+    Idle,
+    Pending,
+}
+
+pin_project! {
+    /// Interval throttler based on a periodic `timerfd`.
+    ///
+    /// Missed ticks are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `timerfd` could not be created, armed, or read, e.g.
+    /// if not used from within a tokio runtime with I/O enabled.
+    #[derive(Debug)]
+    #[project = IntervalThrottlerProjection]
+    pub struct IntervalThrottler<T> {
+        config: ThrottleIntervalConfig,
+        // Created lazily from within the runtime.
+        fd: Option<AsyncFd<OwnedFd>>,
+        ready_immediately: bool,
+        state: IntervalThrottlerState,
+        _marker: PhantomData<T>,
+    }
+}
+
+impl<T> IntervalThrottler<T> {
+    #[must_use]
+    pub(crate) const fn new(config: ThrottleIntervalConfig) -> Self {
+        Self {
+            config,
+            fd: None,
+            ready_immediately: false,
+            state: IntervalThrottlerState::Idle,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Stream for IntervalThrottler<T> {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let IntervalThrottlerProjection {
+            config: ThrottleIntervalConfig { period, edge: _ },
+            fd,
+            ready_immediately,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => Poll::Pending,
+            IntervalThrottlerState::Pending => {
+                if period.is_zero() || *ready_immediately {
+                    *ready_immediately = false;
+                    return Poll::Ready(Some(()));
+                }
+                let fd = fd.as_ref().expect("timerfd armed");
+                poll_timerfd_expired(fd, cx).map(Some)
+            }
+        }
+    }
+}
+
+impl<T> Throttler<T> for IntervalThrottler<T> {
+    fn throttle_pending(self: Pin<&mut Self>, _cx: &mut Context<'_>) {
+        let IntervalThrottlerProjection {
+            config: ThrottleIntervalConfig { period, edge },
+            fd,
+            ready_immediately,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => {
+                *state = IntervalThrottlerState::Pending;
+                if period.is_zero() {
+                    return;
+                }
+                let fd = match fd {
+                    Some(fd) => fd,
+                    None => fd.insert(create_timerfd().expect("create timerfd")),
+                };
+                *ready_immediately = matches!(edge, IntervalEdge::Leading);
+                // Arming the timer also discards all previous expirations.
+                set_timerfd(fd, *period, *period).expect("set timerfd");
+            }
+            IntervalThrottlerState::Pending => (),
+        }
+    }
+
+    fn throttle_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>, next_item: Option<&T>) {
+        let IntervalThrottlerProjection {
+            config: _,
+            fd,
+            ready_immediately: _,
+            state,
+            _marker,
+        } = self.project();
+        match state {
+            IntervalThrottlerState::Idle => unreachable!(),
+            IntervalThrottlerState::Pending => {
+                if next_item.is_none() {
+                    *state = IntervalThrottlerState::Idle;
+                    // Stop the timer until the next item arrives.
+                    if let Some(fd) = fd {
+                        set_timerfd(fd, Duration::ZERO, Duration::ZERO).expect("set timerfd");
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        pin::pin,
+        time::{Duration, Instant},
+    };
+
+    use futures::{StreamExt as _, stream};
+
+    use crate::{IntervalEdge, Sleep as _, StreamExt as _, ThrottleIntervalConfig};
+
+    use super::TimerFdSleep;
+
+    const PERIOD: Duration = Duration::from_micros(250);
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sleep() {
+        runtime().block_on(async {
+            let started_at = Instant::now();
+            TimerFdSleep::sleep(PERIOD).await;
+            assert!(started_at.elapsed() >= PERIOD);
+            TimerFdSleep::sleep(Duration::ZERO).await;
+        });
+    }
+
+    #[test]
+    fn reset_should_re_arm_the_timer() {
+        runtime().block_on(async {
+            let mut sleep = pin!(TimerFdSleep::sleep(PERIOD));
+            sleep.as_mut().await;
+            let started_at = Instant::now();
+            sleep.as_mut().reset(PERIOD);
+            sleep.as_mut().await;
+            assert!(started_at.elapsed() >= PERIOD);
+            sleep.as_mut().reset(Duration::ZERO);
+            sleep.await;
+        });
+    }
+
+    #[test]
+    fn debounce() {
+        runtime().block_on(async {
+            assert_eq!(
+                vec![2],
+                stream::iter(0..3)
+                    .debounce::<TimerFdSleep>(PERIOD, NonZeroUsize::MIN)
+                    .collect::<Vec<_>>()
+                    .await
+            );
+        });
+    }
+
+    #[test]
+    fn throttle_interval_with_sub_millisecond_period() {
+        let num_items = 20;
+        for edge in [IntervalEdge::Leading, IntervalEdge::Trailing] {
+            let config = ThrottleIntervalConfig {
+                period: PERIOD,
+                edge,
+            };
+            runtime().block_on(async {
+                let started_at = Instant::now();
+                let item_count = stream::iter(0..)
+                    .throttle_interval::<TimerFdSleep>(config, NonZeroUsize::MIN)
+                    .take(num_items as usize)
+                    .count()
+                    .await;
+                assert_eq!(num_items as usize, item_count);
+                let min_periods = match edge {
+                    IntervalEdge::Leading => num_items - 1,
+                    IntervalEdge::Trailing => num_items,
+                };
+                assert!(started_at.elapsed() >= PERIOD.saturating_mul(min_periods));
+            });
+        }
+    }
+
+    #[test]
+    fn should_finish_after_non_empty_input_stream_has_completed() {
+        for period in [Duration::ZERO, PERIOD] {
+            for edge in [IntervalEdge::Leading, IntervalEdge::Trailing] {
+                let config = ThrottleIntervalConfig { period, edge };
+                runtime().block_on(async {
+                    assert_eq!(
+                        &[()],
+                        stream::once(async {})
+                            .throttle_interval::<TimerFdSleep>(config, NonZeroUsize::MIN)
+                            .collect::<Vec<_>>()
+                            .await
+                            .as_slice()
+                    );
+                });
+            }
+        }
+    }
+}